@@ -0,0 +1,17 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use simple_base64::{base64_decode_bytes, base64_encode_bytes, Flavour};
+
+fn decode_1mb(c: &mut Criterion) {
+    let bytes: Vec<u8> = (0..1024 * 1024u32).map(|i| (i.wrapping_mul(2_654_435_761) >> 24) as u8).collect();
+    let encoded = base64_encode_bytes(&bytes, &Flavour::Base64Standard);
+    let mut group = c.benchmark_group("decode");
+    group.throughput(Throughput::Bytes(encoded.len() as u64));
+    group.bench_function("1 MB", |b| b.iter(|| base64_decode_bytes(black_box(&encoded), &Flavour::Base64Standard)));
+    // two bytes fewer leave a padded final group
+    let unaligned = base64_encode_bytes(&bytes[..bytes.len() - 2], &Flavour::Base64Standard);
+    group.bench_function("1 MB unaligned", |b| b.iter(|| base64_decode_bytes(black_box(&unaligned), &Flavour::Base64Standard)));
+    group.finish();
+}
+
+criterion_group!(benches, decode_1mb);
+criterion_main!(benches);
\ No newline at end of file
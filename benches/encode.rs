@@ -0,0 +1,16 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use simple_base64::{base64_encode, base64_encode_slice_to_string, Flavour};
+
+fn encode_1mb(c: &mut Criterion) {
+    let text: String = (0..1024 * 1024u32).map(|i| (b'a' + (i.wrapping_mul(2_654_435_761) >> 28) as u8) as char).collect();
+    let mut group = c.benchmark_group("encode");
+    group.throughput(Throughput::Bytes(text.len() as u64));
+    group.bench_function("base64_encode 1 MB", |b| b.iter(|| base64_encode(black_box(text.clone()), &Flavour::default())));
+    group.bench_function("base64_encode_slice_to_string 1 MB", |b| {
+        b.iter(|| base64_encode_slice_to_string(black_box(text.as_bytes())))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, encode_1mb);
+criterion_main!(benches);
\ No newline at end of file
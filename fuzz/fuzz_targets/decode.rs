@@ -0,0 +1,11 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use simple_base64::{try_decode, try_encode};
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(decoded) = try_decode(data) {
+        let encoded = try_encode(&decoded).unwrap();
+        assert_eq!(decoded, try_decode(&encoded).unwrap());
+    }
+});
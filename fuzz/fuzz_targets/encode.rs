@@ -0,0 +1,9 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use simple_base64::{try_decode, try_encode};
+
+fuzz_target!(|data: &[u8]| {
+    let encoded = try_encode(data).unwrap();
+    assert_eq!(data, try_decode(&encoded).unwrap().as_slice());
+});
@@ -0,0 +1,175 @@
+use std::io::Error;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+use crate::errors::Flavour;
+use crate::reader::StreamDecoder;
+use crate::writer::StreamEncoder;
+
+/// Async counterpart of [`crate::Base64Reader`]: decodes the base64 read from a tokio
+/// `AsyncRead`. Malformed input fails the read with `ErrorKind::InvalidData`.
+pub struct AsyncBase64Reader<R: AsyncRead + Unpin> {
+    inner: R,
+    decoder: StreamDecoder,
+    decoded: Vec<u8>,
+    next: usize,
+    eof: bool,
+}
+
+impl<R: AsyncRead + Unpin> AsyncBase64Reader<R> {
+    /// Decodes with the default flavour.
+    pub fn new(inner: R) -> AsyncBase64Reader<R> {
+        AsyncBase64Reader::with_flavour(inner, &Flavour::default())
+    }
+
+    pub fn with_flavour(inner: R, flavour: &Flavour) -> AsyncBase64Reader<R> {
+        AsyncBase64Reader { inner, decoder: StreamDecoder::new(flavour.engine()), decoded: Vec::new(), next: 0,
+            eof: false }
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for AsyncBase64Reader<R> {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<Result<(), Error>> {
+        let this = self.get_mut();
+        while this.next == this.decoded.len() {
+            if this.eof || buf.remaining() == 0 {
+                return Poll::Ready(Ok(()));
+            }
+            let mut chunk = [0; 4096];
+            let mut chunk_buf = ReadBuf::new(&mut chunk);
+            ready!(Pin::new(&mut this.inner).poll_read(cx, &mut chunk_buf))?;
+            this.decoded.clear();
+            this.next = 0;
+            if chunk_buf.filled().is_empty() {
+                this.eof = true;
+                this.decoder.finish(&mut this.decoded)?;
+            } else {
+                this.decoder.push(chunk_buf.filled(), &mut this.decoded)?;
+            }
+        }
+        let size = buf.remaining().min(this.decoded.len() - this.next);
+        buf.put_slice(&this.decoded[this.next..this.next + size]);
+        this.next += size;
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// Encodes everything written to it into a tokio `AsyncWrite`. The final partial group is only
+/// encoded, with its padding, on shutdown.
+pub struct AsyncBase64Writer<W: AsyncWrite + Unpin> {
+    inner: W,
+    encoder: StreamEncoder,
+    encoded: Vec<u8>,
+    next: usize,
+    finished: bool,
+}
+
+impl<W: AsyncWrite + Unpin> AsyncBase64Writer<W> {
+    /// Encodes with the default flavour.
+    pub fn new(inner: W) -> AsyncBase64Writer<W> {
+        AsyncBase64Writer::with_flavour(inner, &Flavour::default())
+    }
+
+    pub fn with_flavour(inner: W, flavour: &Flavour) -> AsyncBase64Writer<W> {
+        AsyncBase64Writer { inner, encoder: StreamEncoder::new(flavour.engine()), encoded: Vec::new(), next: 0,
+            finished: false }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    /// Writes the pending encoded characters to the inner writer.
+    fn poll_drain(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        while self.next < self.encoded.len() {
+            let written = ready!(Pin::new(&mut self.inner).poll_write(cx, &self.encoded[self.next..]))?;
+            if written == 0 {
+                return Poll::Ready(Err(Error::from(std::io::ErrorKind::WriteZero)));
+            }
+            self.next += written;
+        }
+        self.encoded.clear();
+        self.next = 0;
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<W: AsyncWrite + Unpin> AsyncWrite for AsyncBase64Writer<W> {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<Result<usize, Error>> {
+        let this = self.get_mut();
+        ready!(this.poll_drain(cx))?;
+        this.encoder.push(buf, &mut this.encoded);
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        let this = self.get_mut();
+        ready!(this.poll_drain(cx))?;
+        Pin::new(&mut this.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        let this = self.get_mut();
+        if !this.finished {
+            this.encoder.finish(&mut this.encoded);
+            this.finished = true;
+        }
+        ready!(this.poll_drain(cx))?;
+        Pin::new(&mut this.inner).poll_shutdown(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::ErrorKind;
+
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    use super::*;
+    use crate::base64_encode_bytes;
+
+    fn sample() -> Vec<u8> {
+        (0..10_000u32).map(|i| (i * 37 % 256) as u8).collect()
+    }
+
+    #[tokio::test]
+    async fn when_async_copy_should_decode_stream() {
+        let bytes = sample();
+        let encoded = base64_encode_bytes(&bytes, &Flavour::Base64Standard);
+        // a small duplex buffer makes the reader see the stream in many pieces
+        let (mut client, server) = tokio::io::duplex(7);
+        let writer = tokio::spawn(async move {
+            client.write_all(&encoded).await.unwrap();
+        });
+        let mut reader = AsyncBase64Reader::with_flavour(server, &Flavour::Base64Standard);
+        let mut decoded = Vec::new();
+        tokio::io::copy(&mut reader, &mut decoded).await.unwrap();
+        writer.await.unwrap();
+        assert_eq!(bytes, decoded);
+    }
+
+    #[tokio::test]
+    async fn when_async_read_malformed_should_fail_with_invalid_data() {
+        let mut reader = AsyncBase64Reader::with_flavour("TWFuT!E=".as_bytes(), &Flavour::Base64Standard);
+        let error = reader.read_to_end(&mut Vec::new()).await.unwrap_err();
+        assert_eq!(ErrorKind::InvalidData, error.kind());
+        assert_eq!("Invalid character 0x21 at position 5", error.to_string());
+    }
+
+    #[tokio::test]
+    async fn when_async_write_should_encode_on_shutdown() {
+        let bytes = sample();
+        let mut writer = AsyncBase64Writer::with_flavour(Vec::new(), &Flavour::Base64Standard);
+        for chunk in bytes.chunks(7) {
+            writer.write_all(chunk).await.unwrap();
+        }
+        writer.shutdown().await.unwrap();
+        assert_eq!(base64_encode_bytes(&bytes, &Flavour::Base64Standard), writer.into_inner());
+    }
+}
\ No newline at end of file
@@ -0,0 +1,43 @@
+use bytes::{Bytes, BytesMut};
+
+use crate::errors::{Base64Error, Flavour};
+use crate::{decode_calc_byte_size, decode_into, encode_into, encoded_len, strip_bom};
+
+/// Encodes `Bytes` with the default flavour straight into a `BytesMut`, which is then frozen.
+pub fn encode_bytes_crate(input: &Bytes) -> Bytes {
+    let mut res = BytesMut::zeroed(encoded_len(input.len()));
+    encode_into(input, Flavour::default().engine(), &mut res);
+    res.freeze()
+}
+
+/// Decodes `Bytes` holding base64 of the default flavour, the counterpart of [`encode_bytes_crate`].
+/// A leading UTF-8 BOM is skipped, as by [`crate::base64_decode_bytes`].
+pub fn decode_bytes_crate(input: &Bytes) -> Result<Bytes, Base64Error> {
+    let engine = Flavour::default().engine();
+    let input = strip_bom(input);
+    let mut res = BytesMut::zeroed(decode_calc_byte_size(input, engine));
+    let size = decode_into(input, engine, &mut res)?;
+    res.truncate(size);
+    Ok(res.freeze())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DecodeError;
+
+    #[test]
+    fn when_bytes_round_trip_should_return_input() {
+        let input = Bytes::from_static(b"Assuming Man");
+        let encoded = encode_bytes_crate(&input);
+        assert_eq!(Bytes::from_static(b"QXNzdW1pbmcgTWFu"), encoded);
+        assert_eq!(input, decode_bytes_crate(&encoded).unwrap());
+        let error = decode_bytes_crate(&Bytes::from_static(b"QX!z")).unwrap_err();
+        assert_eq!(Some(DecodeError::InvalidByte { position: 2, byte: b'!' }), error.decode_error);
+    }
+
+    #[test]
+    fn when_decode_bytes_with_bom_should_skip_it() {
+        assert_eq!(Bytes::from_static(b"Man"), decode_bytes_crate(&Bytes::from_static(b"\xef\xbb\xbfTWFu")).unwrap());
+    }
+}
\ No newline at end of file
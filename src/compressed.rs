@@ -0,0 +1,48 @@
+use std::io::{Error, Read, Write};
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+
+use crate::reader::Base64Reader;
+use crate::writer::Base64Writer;
+
+/// Gzip compresses `bytes` and encodes the result with the default flavour, streaming the
+/// compressed output straight into the encoder. Worth it for large, compressible blobs.
+pub fn encode_compressed(bytes: &[u8]) -> Result<String, Error> {
+    let mut encoder = GzEncoder::new(Base64Writer::new(Vec::new()), Compression::default());
+    encoder.write_all(bytes)?;
+    let encoded = encoder.finish()?.finish()?;
+    Ok(String::from_utf8(encoded).expect("base64 alphabets are ASCII"))
+}
+
+/// Decodes and decompresses output of [`encode_compressed`]. Malformed base64 or gzip data fails
+/// with `ErrorKind::InvalidData`.
+pub fn decode_compressed(input: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut decompressed = Vec::new();
+    GzDecoder::new(Base64Reader::new(input)).read_to_end(&mut decompressed)?;
+    Ok(decompressed)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::ErrorKind;
+
+    use super::*;
+    use crate::base64_encode_slice_to_string;
+
+    #[test]
+    fn when_compressible_should_round_trip_shorter_than_plain_encoding() {
+        let bytes = "Man is distinguished, not only by his reason. ".repeat(200).into_bytes();
+        let encoded = encode_compressed(&bytes).unwrap();
+        assert!(encoded.len() < base64_encode_slice_to_string(&bytes).len() / 10);
+        assert_eq!(bytes, decode_compressed(encoded.as_bytes()).unwrap());
+        assert_eq!(Vec::<u8>::new(), decode_compressed(encode_compressed(b"").unwrap().as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn when_not_compressed_should_fail_with_invalid_data() {
+        assert_eq!(ErrorKind::InvalidData, decode_compressed(b"TWFu!").unwrap_err().kind());
+        assert!(decode_compressed(b"TWFuTWFu").is_err());
+    }
+}
\ No newline at end of file
@@ -1,23 +1,38 @@
 use lazy_static::lazy_static;
 
-const BASE_64_ENCODING_URL: &'static str = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
-const BASE_64_ENCODING: &'static str =     "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const BASE_64_ENCODING_URL: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+const BASE_64_ENCODING: &str =     "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+// URL safe characters in ASCII order, so encoded strings sort like their input
+const BASE_64_ENCODING_SORTABLE: &str = "-0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ_abcdefghijklmnopqrstuvwxyz";
+// digits first, so the first 16 values are written like hexadecimal digits
+const BASE_64_ENCODING_HEX: &str = "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz-_";
+// alphabet of crypt(3) and of Apache's htpasswd hashes
+const BASE_64_ENCODING_CRYPT: &str = "./0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
 
 pub const PADDING_CHAR: u8 = 61; // '=' character
 
+pub const INVALID_CHAR: u8 = 255; // marks bytes outside of the alphabet in the reverse tables
+pub const PADDING_CODE: u8 = 254; // marks the padding character in the reverse tables
+pub const UTF8_BOM: &[u8] = &[0xef, 0xbb, 0xbf];
+
 pub const BASE_64_ENCODING_CHARS: &[u8] = BASE_64_ENCODING.as_bytes();
 pub const BASE_64_ENCODING_CHARS_URL: &[u8] = BASE_64_ENCODING_URL.as_bytes();
+pub const BASE_64_ENCODING_CHARS_SORTABLE: &[u8] = BASE_64_ENCODING_SORTABLE.as_bytes();
+pub const BASE_64_ENCODING_CHARS_CRYPT: &[u8] = BASE_64_ENCODING_CRYPT.as_bytes();
+pub const BASE_64_ENCODING_CHARS_HEX: &[u8] = BASE_64_ENCODING_HEX.as_bytes();
 
 lazy_static! {
-    pub static ref CHARS_BASE_64_ENCODING: Vec<u8> = compute_reverse_encoding(BASE_64_ENCODING);
-    pub static ref CHARS_BASE_64_ENCODING_URL: Vec<u8> = compute_reverse_encoding(BASE_64_ENCODING_URL);
+    pub static ref CHARS_BASE_64_ENCODING: [u8; 256] = compute_reverse_encoding(BASE_64_ENCODING_CHARS, Some(PADDING_CHAR));
+    pub static ref CHARS_BASE_64_ENCODING_URL: [u8; 256] = compute_reverse_encoding(BASE_64_ENCODING_CHARS_URL, Some(PADDING_CHAR));
 }
 
-fn compute_reverse_encoding(char_set: &str) -> Vec<u8> {
-    let mut encoding: Vec<u8> = vec![0; 127];
-    for (i, b) in char_set.as_bytes().to_vec().iter().enumerate() {
+pub fn compute_reverse_encoding(char_set: &[u8], pad: Option<u8>) -> [u8; 256] {
+    let mut encoding: [u8; 256] = [INVALID_CHAR; 256];
+    for (i, b) in char_set.iter().enumerate() {
         encoding[*b as usize] = i as u8;
     }
-    encoding[PADDING_CHAR as usize] = PADDING_CHAR;
-    return encoding;
-}
\ No newline at end of file
+    if let Some(pad) = pad {
+        encoding[pad as usize] = PADDING_CODE;
+    }
+    encoding
+}
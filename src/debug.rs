@@ -0,0 +1,36 @@
+//! Introspection aids for learning and debugging the encoding, not meant for hot paths.
+
+use crate::bytes_encode_trio;
+use crate::errors::Flavour;
+
+/// Shows how three bytes are regrouped into four sextets and which characters of the default
+/// flavour encode them. For "Man":
+///
+/// ```text
+/// bytes:   01001101 01100001 01101110
+/// sextets: 010011 010110 000101 101110
+/// indices: [19, 22, 5, 46]
+/// chars:   TWFu
+/// ```
+pub fn debug_encode_trio(bytes: [u8; 3]) -> String {
+    let indices = bytes_encode_trio(&bytes);
+    let alphabet = Flavour::default().engine().alphabet();
+    let bits: Vec<String> = bytes.iter().map(|b| format!("{:08b}", b)).collect();
+    let sextets: Vec<String> = indices.iter().map(|i| format!("{:06b}", i)).collect();
+    let chars: String = indices.iter().map(|i| alphabet[*i] as char).collect();
+    format!("bytes:   {}\nsextets: {}\nindices: {:?}\nchars:   {}", bits.join(" "), sextets.join(" "), indices, chars)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn when_debug_encode_trio_should_show_sextets() {
+        let dump = debug_encode_trio(*b"Man");
+        assert!(dump.contains("indices: [19, 22, 5, 46]"));
+        assert!(dump.contains("sextets: 010011 010110 000101 101110"));
+        assert!(dump.starts_with("bytes:   01001101 01100001 01101110\n"));
+        assert!(dump.ends_with("chars:   TWFu"));
+    }
+}
\ No newline at end of file
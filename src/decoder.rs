@@ -0,0 +1,69 @@
+use crate::engine::Base64Engine;
+use crate::errors::Base64Error;
+use crate::{decode_calc_byte_size, decode_into};
+
+/// Decoder keeping its scratch buffer between calls, for servers decoding many small messages.
+///
+/// ```
+/// use simple_base64::Decoder;
+///
+/// let mut decoder = Decoder::default();
+/// let mut out = Vec::new();
+/// decoder.decode("TWFu".as_bytes(), &mut out).unwrap();
+/// assert_eq!("Man".as_bytes(), out.as_slice());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Decoder {
+    engine: Base64Engine,
+    scratch: Vec<u8>,
+}
+
+impl Decoder {
+    pub fn new(engine: Base64Engine) -> Decoder {
+        Decoder { engine, scratch: Vec::new() }
+    }
+
+    /// Decodes `input` into `out`, which is cleared first. Neither `out` nor the internal scratch
+    /// buffer reallocate once they have grown to the size of the largest message.
+    pub fn decode(&mut self, input: &[u8], out: &mut Vec<u8>) -> Result<(), Base64Error> {
+        out.clear();
+        self.engine.collect_significant(input, &mut self.scratch)?;
+        out.resize(decode_calc_byte_size(&self.scratch, &self.engine), 0);
+        let written = decode_into(&self.scratch, &self.engine, out);
+        if written.is_err() {
+            out.clear();
+        }
+        written.map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::LineEnding;
+
+    #[test]
+    fn when_decoder_reused_should_match_one_shot_decode() {
+        let engine = Base64Engine::standard().with_wrap(16, LineEnding::Lf);
+        let mut decoder = Decoder::new(engine.clone());
+        let mut out = Vec::new();
+        for i in 0..10_000usize {
+            let message: Vec<u8> = (0..i % 97).map(|j| (i * 31 + j * 7) as u8).collect();
+            let encoded = engine.encode(&message);
+            decoder.decode(&encoded, &mut out).unwrap();
+            assert_eq!(engine.decode(&encoded).unwrap(), out);
+            assert_eq!(message, out);
+        }
+    }
+
+    #[test]
+    fn when_decoder_fails_should_leave_output_empty() {
+        let mut decoder = Decoder::default();
+        let mut out = vec![1, 2, 3];
+        assert!(decoder.decode("TQ=A".as_bytes(), &mut out).is_err());
+        assert!(out.is_empty());
+        assert!(decoder.decode("TW!u".as_bytes(), &mut out).is_err());
+        decoder.decode("TWE=".as_bytes(), &mut out).unwrap();
+        assert_eq!("Ma".as_bytes(), out.as_slice());
+    }
+}
@@ -0,0 +1,717 @@
+use lazy_static::lazy_static;
+
+use crate::constants::{BASE_64_ENCODING_CHARS, BASE_64_ENCODING_CHARS_CRYPT, BASE_64_ENCODING_CHARS_HEX, BASE_64_ENCODING_CHARS_SORTABLE, BASE_64_ENCODING_CHARS_URL, CHARS_BASE_64_ENCODING, CHARS_BASE_64_ENCODING_URL, compute_reverse_encoding, INVALID_CHAR, PADDING_CHAR, PADDING_CODE};
+use crate::errors::{Base64Error, DecodeError, Flavour};
+use crate::{decode_bytes, encode_bytes, invalid_character};
+
+lazy_static! {
+    static ref STANDARD: Base64Engine = Base64Engine::standard();
+    static ref URL_SAFE: Base64Engine = Base64Engine::url_safe();
+    pub(crate) static ref URL_SAFE_NO_PAD: Base64Engine = Base64Engine::url_safe().no_pad();
+    pub(crate) static ref MIXED: Base64Engine = Base64Engine::standard().with_aliases(&[(b'-', b'+'), (b'_', b'/')]);
+    pub(crate) static ref MIXED_NO_PAD: Base64Engine = Base64Engine::standard().no_pad()
+        .with_aliases(&[(b'-', b'+'), (b'_', b'/')]);
+}
+
+/// Configurable Base 64 encoder and decoder.
+///
+/// The engine is created from one of the named constructors and tweaked with the builder style
+/// methods, e.g. `Base64Engine::url_safe().permissive()`. The default engine uses the standard
+/// alphabet and strict decoding.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Base64Engine {
+    alphabet: [u8; 64],
+    decode_table: [u8; 256],
+    pad: Option<u8>,
+    permissive: bool,
+    case_insensitive: bool,
+    wrap: Option<(usize, LineEnding)>,
+    accepted_pads: Vec<u8>,
+    max_line_length: Option<usize>,
+    post_process: PostProcess,
+}
+
+/// Transformation of the encoded text required by some legacy formats, applied after encoding and
+/// undone before decoding.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum PostProcess {
+    #[default]
+    None,
+    /// The encoded characters are reversed, e.g. `uFWT` for `Man`.
+    Reverse
+}
+
+/// Line separator inserted by wrapping engines.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LineEnding {
+    Lf,
+    CrLf,
+    Cr
+}
+
+impl LineEnding {
+    pub fn as_bytes(&self) -> &'static [u8] {
+        match self {
+            LineEnding::Lf => b"\n",
+            LineEnding::CrLf => b"\r\n",
+            LineEnding::Cr => b"\r"
+        }
+    }
+}
+
+impl Base64Engine {
+    /// Engine using the standard alphabet of RFC 4648 section 4.
+    pub fn standard() -> Base64Engine {
+        Base64Engine::from_tables(BASE_64_ENCODING_CHARS, *CHARS_BASE_64_ENCODING)
+    }
+
+    /// Engine of RFC 3548 section 3, the predecessor of RFC 4648: same alphabet, same padding, so
+    /// identical to [`Base64Engine::standard`]. Named for conformance suites which refer to it.
+    pub fn rfc3548() -> Base64Engine {
+        Base64Engine::standard()
+    }
+
+    /// Engine using the URL and filename safe alphabet of RFC 4648 section 5.
+    pub fn url_safe() -> Base64Engine {
+        Base64Engine::from_tables(BASE_64_ENCODING_CHARS_URL, *CHARS_BASE_64_ENCODING_URL)
+    }
+
+    /// Engine for sortable keys: the URL safe characters in ASCII order and no padding, so encoded
+    /// strings sort lexically in the same order as their inputs do bytewise.
+    pub fn lexicographic() -> Base64Engine {
+        Base64Engine::unpadded(BASE_64_ENCODING_CHARS_SORTABLE)
+    }
+
+    /// Engine with the "hex extended" alphabet `0-9A-Za-z-_`, in the spirit of base32hex: the
+    /// digits come first, so values below 16 are written like hexadecimal digits. Padded with `=`.
+    pub fn hex_extended() -> Base64Engine {
+        Base64Engine::from_tables(BASE_64_ENCODING_CHARS_HEX,
+                                  compute_reverse_encoding(BASE_64_ENCODING_CHARS_HEX, Some(PADDING_CHAR)))
+    }
+
+    /// Engine for the legacy format of crypt(3) and Apache's htpasswd: the alphabet
+    /// `./0-9A-Za-z` and no padding.
+    ///
+    /// Quirks: only the alphabet and the missing padding are reproduced. MD5-crypt (`$1$`,
+    /// `$apr1$`) and SHA-crypt shuffle the digest bytes and pack each group least significant bits
+    /// first, so their hashes must be reordered before decoding; bcrypt uses `./A-Za-z0-9` instead,
+    /// see [`Base64Engine::with_alphabet`].
+    pub fn apache_crypt() -> Base64Engine {
+        Base64Engine::unpadded(BASE_64_ENCODING_CHARS_CRYPT)
+    }
+
+    /// Engine for one of the built in alphabets, which are known to be valid, without padding.
+    fn unpadded(alphabet: &[u8]) -> Base64Engine {
+        let mut engine = Base64Engine::from_tables(alphabet, [INVALID_CHAR; 256]);
+        engine.pad = None;
+        engine.rebuild_decode_table();
+        engine
+    }
+
+    /// Engine which only encodes: without a decode table the alphabet needs no checks.
+    pub(crate) fn encoding_only(alphabet: &[u8; 64], pad: Option<u8>) -> Base64Engine {
+        let mut engine = Base64Engine::from_tables(alphabet, [INVALID_CHAR; 256]);
+        engine.pad = pad;
+        engine
+    }
+
+    fn from_tables(alphabet: &[u8], decode_table: [u8; 256]) -> Base64Engine {
+        let mut chars = [0; 64];
+        chars.copy_from_slice(alphabet);
+        Base64Engine { alphabet: chars, decode_table, pad: Some(PADDING_CHAR), permissive: false, case_insensitive: false,
+            wrap: None, accepted_pads: vec![], max_line_length: None, post_process: PostProcess::None }
+    }
+
+    /// Replaces the alphabet with a custom one of 64 distinct characters.
+    ///
+    /// Fails if the alphabet has the wrong size, repeats a character or, when case insensitive
+    /// decoding was requested, contains two letters which only differ in case.
+    pub fn with_alphabet(mut self, alphabet: &[u8]) -> Result<Base64Engine, Base64Error> {
+        check_alphabet(alphabet)?;
+        if self.case_insensitive {
+            check_case_collisions(alphabet)?;
+        }
+        self.alphabet.copy_from_slice(alphabet);
+        self.rebuild_decode_table();
+        Ok(self)
+    }
+
+    /// Replaces only the 62nd and 63rd characters, keeping `A-Za-z0-9`, e.g. `-` and `_` for the
+    /// URL safe alphabet or `+` and `,` for IMAP mailbox names.
+    ///
+    /// Fails if either character is alphanumeric or both are the same.
+    pub fn with_chars_62_63(self, c62: u8, c63: u8) -> Result<Base64Engine, Base64Error> {
+        if let Some(c) = [c62, c63].into_iter().find(u8::is_ascii_alphanumeric) {
+            return Err(Base64Error::new(&format!("Character '{}' collides with the alphanumeric characters of the alphabet",
+                                                 c as char)));
+        }
+        let mut alphabet = self.alphabet;
+        alphabet[62] = c62;
+        alphabet[63] = c63;
+        self.with_alphabet(&alphabet)
+    }
+
+    /// Replaces the padding character `=`, e.g. with `~`. Encoding pads with it and decoding only
+    /// accepts it as padding.
+    ///
+    /// Fails if the character is part of the alphabet.
+    pub fn with_pad(mut self, pad: u8) -> Result<Base64Engine, Base64Error> {
+        if self.alphabet.contains(&pad) {
+            return Err(Base64Error::new(&format!("Padding character '{}' is part of the alphabet", pad as char)));
+        }
+        self.pad = Some(pad);
+        self.rebuild_decode_table();
+        Ok(self)
+    }
+
+    /// Omits the padding: encoding leaves the final group short, e.g. "TQ" for "M", and decoding
+    /// expects such short groups and rejects padding characters.
+    pub fn no_pad(mut self) -> Base64Engine {
+        self.pad = None;
+        self.rebuild_decode_table();
+        self
+    }
+
+    /// Also accepts each of `pads` as padding of the final group while decoding, for encoders
+    /// which pad with e.g. `.` or `*`. Encoding keeps using the padding character.
+    pub fn with_accepted_pads(mut self, pads: &[u8]) -> Base64Engine {
+        self.accepted_pads = pads.to_vec();
+        self.rebuild_decode_table();
+        self
+    }
+
+    /// Decodes letters regardless of their case. Only makes sense for alphabets which do not
+    /// contain both cases of a letter, see [`Base64Engine::with_alphabet`].
+    pub fn case_insensitive(mut self) -> Base64Engine {
+        self.case_insensitive = true;
+        self.rebuild_decode_table();
+        self
+    }
+
+    pub fn is_case_insensitive(&self) -> bool {
+        self.case_insensitive
+    }
+
+    fn rebuild_decode_table(&mut self) {
+        let mut table = compute_reverse_encoding(&self.alphabet, self.pad);
+        if self.case_insensitive {
+            for (i, b) in self.alphabet.iter().enumerate() {
+                if b.is_ascii_alphabetic() {
+                    table[b.to_ascii_lowercase() as usize] = i as u8;
+                    table[b.to_ascii_uppercase() as usize] = i as u8;
+                }
+            }
+        }
+        for pad in &self.accepted_pads {
+            table[*pad as usize] = PADDING_CODE;
+        }
+        self.decode_table = table;
+    }
+
+    /// Inserts a line ending after every `line_length` encoded characters, e.g. 76 and
+    /// [`LineEnding::CrLf`] for MIME. The decoder skips both `\r` and `\n`, whatever the ending.
+    /// A `line_length` of zero fails [`Base64Engine::validate`] and does not wrap at all.
+    pub fn with_wrap(mut self, line_length: usize, line_ending: LineEnding) -> Base64Engine {
+        self.wrap = Some((line_length, line_ending));
+        self
+    }
+
+    /// Makes decoding fail as soon as more than `limit` significant characters follow each other
+    /// without a `\r` or `\n`, so untrusted wrapped input cannot smuggle in one huge line.
+    pub fn with_max_line_length(mut self, limit: usize) -> Base64Engine {
+        self.max_line_length = Some(limit);
+        self
+    }
+
+    /// Transforms the encoded characters after encoding, before any line wrapping, and undoes it
+    /// before decoding.
+    pub fn with_post_process(mut self, post_process: PostProcess) -> Base64Engine {
+        self.post_process = post_process;
+        self
+    }
+
+    /// Checks the whole configuration: alphabet size and uniqueness, padding character, case
+    /// insensitivity, line wrapping and maximum line length. Call it (or [`Base64Engine::build`])
+    /// once at startup to find configuration errors before the first encode.
+    pub fn validate(&self) -> Result<(), Base64Error> {
+        check_alphabet(&self.alphabet)?;
+        if let Some(pad) = self.pad.filter(|pad| self.alphabet.contains(pad)) {
+            return Err(Base64Error::new(&format!("Padding character '{}' is part of the alphabet", pad as char)));
+        }
+        if let Some(pad) = self.accepted_pads.iter().find(|pad| self.alphabet.contains(pad)) {
+            return Err(Base64Error::new(&format!("Accepted padding character '{}' is part of the alphabet", *pad as char)));
+        }
+        if self.case_insensitive {
+            check_case_collisions(&self.alphabet)?;
+        }
+        if let Some((0, _)) = self.wrap {
+            return Err(Base64Error::new("Line length must be greater than zero"));
+        }
+        if self.max_line_length == Some(0) {
+            return Err(Base64Error::new("Maximum line length must be greater than zero"));
+        }
+        Ok(())
+    }
+
+    /// Finishes the configuration, returning the engine only if [`Base64Engine::validate`] passes.
+    pub fn build(self) -> Result<Base64Engine, Base64Error> {
+        self.validate()?;
+        Ok(self)
+    }
+
+    /// Invalid characters make decoding fail. This is the default.
+    pub fn strict(mut self) -> Base64Engine {
+        self.permissive = false;
+        self
+    }
+
+    /// Invalid characters are skipped while decoding, like MIME decoders do (RFC 2045 section 6.8).
+    pub fn permissive(mut self) -> Base64Engine {
+        self.permissive = true;
+        self
+    }
+
+    pub fn is_permissive(&self) -> bool {
+        self.permissive
+    }
+
+    /// Conformance mode of RFC 4648 section 3.1: no line wrapping and strict decoding, so any
+    /// whitespace in the input, line breaks included, is an error.
+    pub fn rfc4648_strict(mut self) -> Base64Engine {
+        self.wrap = None;
+        self.permissive = false;
+        self
+    }
+
+    /// MIME mode of RFC 2045 section 6.8: lines of 76 characters ended by CRLF, and decoding which
+    /// skips characters outside of the alphabet.
+    pub fn mime(self) -> Base64Engine {
+        self.with_wrap(76, LineEnding::CrLf).permissive()
+    }
+
+    pub fn encode(&self, bytes: &[u8]) -> Vec<u8> {
+        let mut encoded = encode_bytes(bytes, self);
+        if self.post_process == PostProcess::Reverse {
+            encoded.reverse();
+        }
+        match &self.wrap {
+            Some((line_length, line_ending)) if *line_length > 0 => wrap_lines(&encoded, *line_length, line_ending.as_bytes()),
+            _ => encoded
+        }
+    }
+
+    pub fn decode(&self, bytes: &[u8]) -> Result<Vec<u8>, Base64Error> {
+        if !self.permissive && self.wrap.is_none() && self.max_line_length.is_none()
+            && self.post_process == PostProcess::None {
+            // nothing to skip or undo, so the input is decoded without copying it
+            return decode_bytes(bytes, self);
+        }
+        let mut significant = Vec::with_capacity(bytes.len());
+        self.collect_significant(bytes, &mut significant)?;
+        decode_bytes(&significant, self)
+    }
+
+    /// Replaces the content of `significant` with the characters of `bytes` which are decoded,
+    /// dropping separators and, when permissive, invalid characters. The quartets are formed
+    /// afterwards, so line breaks may fall anywhere, not only at multiples of 4. A reversing
+    /// [`PostProcess`] is undone here.
+    pub(crate) fn collect_significant(&self, bytes: &[u8], significant: &mut Vec<u8>) -> Result<(), Base64Error> {
+        significant.clear();
+        let mut line_length = 0;
+        for (position, b) in bytes.iter().enumerate() {
+            if *b == b'\r' || *b == b'\n' {
+                line_length = 0;
+            }
+            if self.decode_table[*b as usize] != INVALID_CHAR {
+                line_length += 1;
+                if let Some(limit) = self.max_line_length.filter(|limit| line_length > *limit) {
+                    return Err(Base64Error::malformed(DecodeError::LimitExceeded, &format!(
+                        "More than {} characters without a line break at position {}", limit, position)));
+                }
+                significant.push(*b);
+            } else if !self.permissive && !self.is_separator(*b) {
+                return Err(invalid_character(*b, position));
+            }
+        }
+        if self.post_process == PostProcess::Reverse {
+            significant.reverse();
+        }
+        Ok(())
+    }
+
+    fn is_separator(&self, b: u8) -> bool {
+        self.wrap.is_some() && (b == b'\r' || b == b'\n')
+    }
+
+    /// Decodes each `(alias, char)` alias like the alphabet character. Builder calls which
+    /// rebuild the decode table drop the aliases, so this must come last.
+    pub(crate) fn with_aliases(mut self, aliases: &[(u8, u8)]) -> Base64Engine {
+        for (alias, c) in aliases {
+            self.decode_table[*alias as usize] = self.decode_table[*c as usize];
+        }
+        self
+    }
+
+    /// The 64 characters encoding the sextets 0 to 63, in order.
+    pub fn alphabet(&self) -> &[u8; 64] {
+        &self.alphabet
+    }
+
+    pub(crate) fn decode_table(&self) -> &[u8; 256] {
+        &self.decode_table
+    }
+
+    /// The padding character, or `None` for engines which omit the padding.
+    pub fn pad_char(&self) -> Option<u8> {
+        self.pad
+    }
+
+    pub(crate) fn is_pad(&self, b: u8) -> bool {
+        Some(b) == self.pad || self.accepted_pads.contains(&b)
+    }
+}
+
+fn check_alphabet(alphabet: &[u8]) -> Result<(), Base64Error> {
+    if alphabet.len() != 64 {
+        return Err(Base64Error::new(&format!("Alphabet must have 64 characters, found {}", alphabet.len())));
+    }
+    if let Some(i) = alphabet.iter().position(|b| !b.is_ascii_graphic()) {
+        return Err(Base64Error::new(&format!("Alphabet character 0x{:02x} at position {} is not printable ASCII", alphabet[i], i)));
+    }
+    if let Some(i) = (1..alphabet.len()).find(|&i| alphabet[..i].contains(&alphabet[i])) {
+        return Err(Base64Error::new(&format!("Alphabet repeats character 0x{:02x} at position {}", alphabet[i], i)));
+    }
+    Ok(())
+}
+
+fn wrap_lines(encoded: &[u8], line_length: usize, separator: &[u8]) -> Vec<u8> {
+    let lines = encoded.len().div_ceil(line_length);
+    let mut res = Vec::with_capacity(encoded.len() + lines.saturating_sub(1) * separator.len());
+    for (i, line) in encoded.chunks(line_length).enumerate() {
+        if i > 0 {
+            res.extend_from_slice(separator);
+        }
+        res.extend_from_slice(line);
+    }
+    res
+}
+
+fn check_case_collisions(alphabet: &[u8]) -> Result<(), Base64Error> {
+    for (i, b) in alphabet.iter().enumerate() {
+        if b.is_ascii_alphabetic() && alphabet[..i].iter().any(|c| c.eq_ignore_ascii_case(b)) {
+            return Err(Base64Error::new(&format!(
+                "Alphabet character '{}' at position {} collides with another under case insensitive decoding",
+                *b as char, i)));
+        }
+    }
+    Ok(())
+}
+
+impl Default for Base64Engine {
+    fn default() -> Base64Engine {
+        Base64Engine::standard()
+    }
+}
+
+impl Flavour {
+    /// Returns the shared engine for this flavour, with strict decoding.
+    pub fn engine(&self) -> &'static Base64Engine {
+        match self {
+            Flavour::Base64Standard => &STANDARD,
+            Flavour::Base64Url => &URL_SAFE,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn when_default_engine_should_be_strict() {
+        let engine = Base64Engine::default();
+        assert!(!engine.is_permissive());
+        assert_eq!(Base64Engine::standard(), engine);
+    }
+
+    #[test]
+    fn when_corrupt_input_should_fail_strict_and_succeed_permissive() {
+        let corrupt = "TW!F u\u{e9}".as_bytes();
+        let strict = Base64Engine::standard().strict().decode(corrupt);
+        assert!(strict.is_err());
+        assert_eq!("Invalid character 0x21 at position 2", strict.unwrap_err().msg);
+        let permissive = Base64Engine::standard().permissive().decode(corrupt);
+        assert_eq!("Man".as_bytes(), permissive.unwrap().as_slice());
+    }
+
+    #[test]
+    fn when_case_colliding_alphabet_and_case_insensitive_should_fail() {
+        let res = Base64Engine::standard().case_insensitive().with_alphabet(BASE_64_ENCODING_CHARS);
+        assert!(res.is_err());
+        assert!(res.unwrap_err().msg.contains("'a' at position 26"));
+        assert!(Base64Engine::standard().with_alphabet(BASE_64_ENCODING_CHARS).is_ok());
+    }
+
+    #[test]
+    fn when_custom_alphabet_should_round_trip_case_insensitive() {
+        let alphabet = "ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789!#$%&()*,-.:;<>?@[]^_`{|}~'\\".as_bytes();
+        let engine = Base64Engine::standard().case_insensitive().with_alphabet(alphabet).unwrap();
+        let encoded = engine.encode("Man and woman".as_bytes());
+        let lower = encoded.to_ascii_lowercase();
+        assert_ne!(encoded, lower);
+        assert_eq!("Man and woman".as_bytes(), engine.decode(&lower).unwrap().as_slice());
+    }
+
+    #[test]
+    fn when_invalid_alphabet_should_fail() {
+        assert!(Base64Engine::standard().with_alphabet("ABC".as_bytes()).is_err());
+        let mut repeated = BASE_64_ENCODING_CHARS.to_vec();
+        repeated[63] = b'A';
+        let res = Base64Engine::standard().with_alphabet(&repeated);
+        assert_eq!("Alphabet repeats character 0x41 at position 63", res.unwrap_err().msg);
+    }
+
+    #[test]
+    fn when_validate_should_reject_invalid_configurations() {
+        let mut with_pad = BASE_64_ENCODING_CHARS.to_vec();
+        with_pad[63] = b'=';
+        let pad_collision = Base64Engine::standard().with_alphabet(&with_pad).unwrap();
+        assert_eq!("Padding character '=' is part of the alphabet", pad_collision.validate().unwrap_err().msg);
+        assert!(Base64Engine::standard().case_insensitive().build().is_err());
+        assert!(Base64Engine::standard().with_wrap(0, LineEnding::Lf).build().is_err());
+        assert!(Base64Engine::standard().with_alphabet(&[b' '; 64]).is_err());
+        assert!(Base64Engine::standard().with_wrap(76, LineEnding::CrLf).build().is_ok());
+        assert!(Base64Engine::url_safe().permissive().build().is_ok());
+    }
+
+    #[test]
+    fn when_pad_is_part_of_the_alphabet_should_reject_it() {
+        let res = Base64Engine::standard().with_pad(b'A');
+        assert_eq!("Padding character 'A' is part of the alphabet", res.unwrap_err().msg);
+        assert!(Base64Engine::url_safe().with_pad(b'+').is_ok());
+        assert!(Base64Engine::standard().with_pad(b'+').is_err());
+    }
+
+    #[test]
+    fn when_wrap_is_zero_should_encode_without_line_breaks() {
+        let engine = Base64Engine::standard().with_wrap(0, LineEnding::Lf);
+        assert_eq!("TWFu".as_bytes(), engine.encode("Man".as_bytes()).as_slice());
+        assert!(engine.validate().is_err());
+    }
+
+    #[test]
+    fn when_wrap_should_insert_separator_and_decode_back() {
+        let engine = Base64Engine::standard().with_wrap(8, LineEnding::Lf).build().unwrap();
+        let encoded = engine.encode("Assuming Man".as_bytes());
+        assert_eq!("QXNzdW1p\nbmcgTWFu", std::str::from_utf8(&encoded).unwrap());
+        assert_eq!("Assuming Man".as_bytes(), engine.decode(&encoded).unwrap().as_slice());
+        assert!(Base64Engine::standard().decode(&encoded).is_err());
+    }
+
+    #[test]
+    fn when_wrapped_at_odd_columns_should_decode() {
+        let bytes: Vec<u8> = (0..200u8).collect();
+        let encoded = Base64Engine::standard().encode(&bytes);
+        let engine = Base64Engine::standard().with_wrap(64, LineEnding::Lf);
+        for (column, separator) in [(1, "\n"), (3, "\r\n"), (5, "\n"), (63, "\r\n"), (66, "\r")] {
+            let mut wrapped = Vec::new();
+            for (i, line) in encoded.chunks(column).enumerate() {
+                if i > 0 {
+                    wrapped.extend_from_slice(separator.as_bytes());
+                }
+                wrapped.extend_from_slice(line);
+            }
+            assert_eq!(bytes, engine.decode(&wrapped).unwrap(), "wrapped at {}", column);
+        }
+    }
+
+    #[test]
+    fn when_rfc4648_strict_should_reject_whitespace() {
+        let strict = Base64Engine::standard().mime().rfc4648_strict();
+        assert_eq!("Invalid character 0x0a at position 2", strict.decode("TW\nFu".as_bytes()).unwrap_err().msg);
+        assert!(strict.decode("TWFu ".as_bytes()).is_err());
+        assert_eq!("Man".as_bytes(), strict.decode("TWFu".as_bytes()).unwrap().as_slice());
+        let mime = Base64Engine::standard().mime();
+        assert_eq!("Man".as_bytes(), mime.decode("TW\nFu".as_bytes()).unwrap().as_slice());
+        assert_eq!(78, mime.encode(&[0; 60]).iter().position(|&b| b == b'\n').unwrap() + 1);
+    }
+
+    #[test]
+    fn when_url_safe_no_pad_wrapped_should_round_trip() {
+        let engine = Base64Engine::url_safe().no_pad().with_wrap(64, LineEnding::Lf).build().unwrap();
+        for size in [0, 1, 47, 48, 49, 100, 1000] {
+            let bytes: Vec<u8> = (0..size).map(|i| (i * 59 % 256) as u8).collect();
+            let encoded = engine.encode(&bytes);
+            let lines: Vec<&[u8]> = encoded.split(|&b| b == b'\n').collect();
+            assert!(lines[..lines.len() - 1].iter().all(|line| line.len() == 64));
+            assert_eq!(Base64Engine::url_safe().no_pad().encode(&bytes), lines.concat());
+            assert!(!encoded.contains(&b'=') && !encoded.ends_with(b"\n"));
+            assert_eq!(bytes, engine.decode(&encoded).unwrap());
+        }
+    }
+
+    #[test]
+    fn when_wrap_crlf_should_insert_crlf_at_column() {
+        let engine = Base64Engine::standard().with_wrap(4, LineEnding::CrLf);
+        let encoded = engine.encode("ManManMa".as_bytes());
+        assert_eq!("TWFu\r\nTWFu\r\nTWE=", std::str::from_utf8(&encoded).unwrap());
+        assert_eq!("ManManMa".as_bytes(), engine.decode(&encoded).unwrap().as_slice());
+        let encoded = Base64Engine::standard().with_wrap(6, LineEnding::Cr).encode("ManMan".as_bytes());
+        assert_eq!("TWFuTW\rFu", std::str::from_utf8(&encoded).unwrap());
+        assert_eq!(b"\n", LineEnding::Lf.as_bytes());
+    }
+
+    #[test]
+    fn when_accepted_pads_should_decode_them_as_padding() {
+        let engine = Base64Engine::standard().with_accepted_pads(b".*");
+        assert_eq!("Ma".as_bytes(), engine.decode("TWE.".as_bytes()).unwrap().as_slice());
+        assert_eq!("M".as_bytes(), engine.decode("TQ.*".as_bytes()).unwrap().as_slice());
+        assert_eq!("M".as_bytes(), engine.decode("TQ==".as_bytes()).unwrap().as_slice());
+        assert_eq!("TWE=".as_bytes(), engine.encode("Ma".as_bytes()).as_slice());
+        assert!(Base64Engine::standard().decode("TWE.".as_bytes()).is_err());
+        let res = Base64Engine::standard().with_accepted_pads(b"A").build();
+        assert_eq!("Accepted padding character 'A' is part of the alphabet", res.unwrap_err().msg);
+    }
+
+    #[test]
+    fn when_chars_62_63_should_replace_last_two() {
+        let engine = Base64Engine::standard().with_chars_62_63(b'-', b'_').unwrap();
+        assert_eq!(Base64Engine::url_safe().alphabet(), engine.alphabet());
+        assert_eq!("-_8=".as_bytes(), engine.encode(&[0xfb, 0xff]).as_slice());
+        assert_eq!(vec![0xfb, 0xff], engine.decode("-_8=".as_bytes()).unwrap());
+        assert!(engine.decode("+/8=".as_bytes()).is_err());
+        let res = Base64Engine::standard().with_chars_62_63(b'!', b'a');
+        assert_eq!("Character 'a' collides with the alphanumeric characters of the alphabet", res.unwrap_err().msg);
+        assert!(Base64Engine::standard().with_chars_62_63(b'!', b'!').is_err());
+    }
+
+    #[test]
+    fn when_lexicographic_should_preserve_order() {
+        let engine = Base64Engine::lexicographic();
+        assert!(engine.validate().is_ok());
+        assert!(engine.alphabet().windows(2).all(|pair| pair[0] < pair[1]));
+        let mut state: u32 = 0x2545_f491;
+        let mut inputs: Vec<Vec<u8>> = (0..500).map(|_| {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state.to_be_bytes()[..(state % 5) as usize].to_vec()
+        }).collect();
+        inputs.extend([vec![], vec![0], vec![0, 0], vec![255], vec![255, 255, 255]]);
+        let mut encoded: Vec<Vec<u8>> = inputs.iter().map(|input| engine.encode(input)).collect();
+        inputs.sort();
+        encoded.sort();
+        let decoded: Vec<Vec<u8>> = encoded.iter().map(|e| engine.decode(e).unwrap()).collect();
+        assert_eq!(inputs, decoded);
+    }
+
+    #[test]
+    fn when_apache_crypt_should_use_crypt_alphabet_without_padding() {
+        let engine = Base64Engine::apache_crypt();
+        assert!(engine.validate().is_ok());
+        assert_eq!(None, engine.pad_char());
+        for (bytes, encoded) in [("Man".as_bytes(), "HK3i"), ("Ma".as_bytes(), "HK2"), (&[0xff, 0xff, 0xff, 0x00], "zzzz..")] {
+            assert_eq!(encoded.as_bytes(), engine.encode(bytes).as_slice());
+            assert_eq!(bytes, engine.decode(encoded.as_bytes()).unwrap().as_slice());
+        }
+        assert!(engine.decode("HK2=".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn when_query_configuration_should_return_alphabet_and_pad() {
+        let engine = Base64Engine::standard();
+        assert_eq!(b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/", engine.alphabet());
+        assert_eq!(Some(b'='), engine.pad_char());
+        assert_eq!(b'_', Base64Engine::url_safe().alphabet()[63]);
+        assert_eq!(None, Base64Engine::url_safe().no_pad().pad_char());
+    }
+
+    #[test]
+    fn when_url_safe_with_custom_pad_should_round_trip() {
+        let engine = Base64Engine::url_safe().with_pad(b'~').unwrap().build().unwrap();
+        let encoded = engine.encode(&[0xfb, 0xff]);
+        assert_eq!("-_8~".as_bytes(), encoded.as_slice());
+        assert_eq!(vec![0xfb, 0xff], engine.decode(&encoded).unwrap());
+        assert_eq!("TQ~~".as_bytes(), engine.encode("M".as_bytes()).as_slice());
+        assert_eq!("M".as_bytes(), engine.decode("TQ~~".as_bytes()).unwrap().as_slice());
+        assert_eq!("Man".as_bytes(), engine.decode("TWFu".as_bytes()).unwrap().as_slice());
+        assert!(engine.decode("TQ==".as_bytes()).is_err());
+        assert!(Base64Engine::url_safe().decode("TQ~~".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn when_no_pad_should_leave_final_group_short() {
+        let engine = Base64Engine::standard().no_pad();
+        for (plain, encoded) in [("M", "TQ"), ("Ma", "TWE"), ("Man", "TWFu"), ("Man M", "TWFuIE0"), ("", "")] {
+            assert_eq!(encoded.as_bytes(), engine.encode(plain.as_bytes()).as_slice());
+            assert_eq!(plain.as_bytes(), engine.decode(encoded.as_bytes()).unwrap().as_slice());
+        }
+        assert!(engine.decode("TQ==".as_bytes()).is_err());
+        assert!(engine.decode("TWFuT".as_bytes()).is_err());
+        assert!(Base64Engine::standard().decode("TQ".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn when_permissive_should_skip_chars_of_other_alphabet() {
+        let decoded = Base64Engine::url_safe().permissive().decode("TW+Fu".as_bytes());
+        assert_eq!("Man".as_bytes(), decoded.unwrap().as_slice());
+        assert!(Base64Engine::url_safe().decode("TW+Fu".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn when_line_exceeds_max_line_length_should_fail() {
+        let engine = Base64Engine::standard().mime().with_max_line_length(76).build().unwrap();
+        let bytes: Vec<u8> = (0..=255u8).collect();
+        let wrapped = engine.encode(&bytes);
+        assert_eq!(bytes, engine.decode(&wrapped).unwrap());
+        let unbroken = Base64Engine::standard().encode(&bytes);
+        let res = engine.decode(&unbroken);
+        assert_eq!("More than 76 characters without a line break at position 76", res.unwrap_err().msg);
+        let res = Base64Engine::standard().with_max_line_length(0).build();
+        assert_eq!("Maximum line length must be greater than zero", res.unwrap_err().msg);
+    }
+
+    #[test]
+    fn when_rfc3548_should_match_the_examples_of_the_rfc() {
+        // RFC 3548 section 7
+        let vectors: [(&[u8], &str); 3] = [
+            (&[0x14, 0xfb, 0x9c, 0x03, 0xd9, 0x7e], "FPucA9l+"),
+            (&[0x14, 0xfb, 0x9c, 0x03, 0xd9], "FPucA9k="),
+            (&[0x14, 0xfb, 0x9c, 0x03], "FPucAw==")
+        ];
+        let engine = Base64Engine::rfc3548();
+        assert_eq!(Base64Engine::standard(), engine);
+        for (bytes, encoded) in vectors {
+            assert_eq!(encoded.as_bytes(), engine.encode(bytes));
+            assert_eq!(bytes, engine.decode(encoded.as_bytes()).unwrap());
+        }
+    }
+
+    #[test]
+    fn when_post_process_reverse_should_reverse_encoded_text() {
+        let engine = Base64Engine::standard().with_post_process(PostProcess::Reverse);
+        assert_eq!("uFWT".as_bytes(), engine.encode(b"Man"));
+        assert_eq!("=EWTuFWT".as_bytes(), engine.encode(b"ManMa"));
+        assert_eq!(b"Man".to_vec(), engine.decode(b"uFWT").unwrap());
+        assert_eq!(b"ManMa".to_vec(), engine.decode(b"=EWTuFWT").unwrap());
+        let wrapped = engine.with_wrap(4, LineEnding::Lf);
+        assert_eq!("=EWT\nuFWT".as_bytes(), wrapped.encode(b"ManMa"));
+        assert_eq!(b"ManMa".to_vec(), wrapped.decode(b"=EWT\nuFWT").unwrap());
+        let mut out = Vec::new();
+        crate::Decoder::new(wrapped).decode(b"=EWT\nuFWT", &mut out).unwrap();
+        assert_eq!(b"ManMa".to_vec(), out);
+    }
+
+    #[test]
+    fn when_hex_extended_should_round_trip_with_digits_first() {
+        let engine = Base64Engine::hex_extended();
+        assert!(engine.validate().is_ok());
+        assert_eq!("0123".as_bytes(), engine.encode(&[0x00, 0x10, 0x83]));
+        assert_eq!("JM5k".as_bytes(), engine.encode(b"Man"));
+        assert_eq!("JG==".as_bytes(), engine.encode(b"M"));
+        assert_eq!("-__-".as_bytes(), engine.encode(&[0xfb, 0xff, 0xfe]));
+        let bytes: Vec<u8> = (0..=255u8).collect();
+        assert_eq!(bytes, engine.decode(&engine.encode(&bytes)).unwrap());
+        assert!(engine.decode(b"TWF+").is_err());
+    }
+}
\ No newline at end of file
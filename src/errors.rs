@@ -1,5 +1,6 @@
-use std::fmt;
+use std::error::Error;
 use std::str::Utf8Error;
+use std::{fmt, io};
 
 #[derive(Debug)]
 pub struct PaddingError;
@@ -10,10 +11,82 @@ impl fmt::Display for PaddingError {
     }
 }
 
+/// Why the input could not be decoded, so callers can react to a cut stream differently than to
+/// a foreign character. More kinds may be added, so matches need a wildcard arm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DecodeError {
+    /// The input is cut short, e.g. a final group of a single character, or does not decode to the
+    /// expected number of bytes.
+    InvalidLength,
+    /// A character outside of the alphabet, or padding where data was expected.
+    InvalidByte { position: usize, byte: u8 },
+    /// Padding is missing, has the wrong length or is followed by more data.
+    InvalidPadding,
+    /// The input, or what it decodes to, is larger than a limit set by the caller.
+    LimitExceeded,
+    /// The text around the base64 is malformed, e.g. a missing frame, a wrong number of segments
+    /// or an unknown encoding name.
+    InvalidFormat
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecodeError::InvalidLength => write!(f, "Invalid length"),
+            DecodeError::InvalidByte { position, byte } =>
+                write!(f, "Invalid character 0x{:02x} at position {}", byte, position),
+            DecodeError::InvalidPadding => write!(f, "Invalid padding"),
+            DecodeError::LimitExceeded => write!(f, "Limit exceeded"),
+            DecodeError::InvalidFormat => write!(f, "Invalid format")
+        }
+    }
+}
+
+impl Error for DecodeError {}
+
+/// The error of every fallible function. Decoding failures carry their [`DecodeError`] kind next to
+/// a message with the details, such as the offending position. Fields may be added, so build one
+/// with [`Base64Error::new`] rather than a struct literal.
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub struct Base64Error {
     pub msg: String,
-    pub utf8_error: Option<Utf8Error>
+    pub utf8_error: Option<Utf8Error>,
+    /// Set when the error comes from malformed input rather than e.g. a bad configuration or a
+    /// failing writer. Always set by the decode functions when the input is at fault.
+    pub decode_error: Option<DecodeError>
+}
+
+impl Base64Error {
+    pub fn new(msg: &str) -> Base64Error {
+        Base64Error { msg: msg.to_string(), utf8_error: None, decode_error: None }
+    }
+
+    /// An error about malformed input, with a message giving more detail than `kind`.
+    pub(crate) fn malformed(kind: DecodeError, msg: &str) -> Base64Error {
+        Base64Error { msg: msg.to_string(), utf8_error: None, decode_error: Some(kind) }
+    }
+}
+
+#[cfg(feature = "http")]
+impl Base64Error {
+    /// Maps the error to an HTTP status and a message safe to show to clients: malformed input,
+    /// input over a limit or invalid UTF-8 is the client's fault (400), anything else, such as an
+    /// I/O failure or a bad configuration, is a server error (500). Positions, characters and
+    /// other details of `msg` are left out.
+    pub fn as_http(&self) -> (u16, String) {
+        let message = match (self.decode_error, self.utf8_error) {
+            (Some(DecodeError::InvalidLength), _) => "Invalid base64 length",
+            (Some(DecodeError::InvalidByte { .. }), _) => "Invalid base64 character",
+            (Some(DecodeError::InvalidPadding), _) => "Invalid base64 padding",
+            (Some(DecodeError::LimitExceeded), _) => "Base64 input too large",
+            (Some(DecodeError::InvalidFormat), _) => "Malformed input",
+            (None, Some(_)) => "Decoded data is not valid UTF-8",
+            (None, None) => return (500, "Internal server error".to_string())
+        };
+        (400, message.to_string())
+    }
 }
 
 impl fmt::Display for Base64Error {
@@ -22,8 +95,95 @@ impl fmt::Display for Base64Error {
     }
 }
 
-#[derive(PartialEq)]
+impl Error for Base64Error {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.utf8_error.as_ref().map(|error| error as &(dyn Error + 'static))
+    }
+}
+
+impl From<DecodeError> for Base64Error {
+    fn from(error: DecodeError) -> Base64Error {
+        Base64Error::malformed(error, &error.to_string())
+    }
+}
+
+/// Malformed input read from a file or a stream becomes `ErrorKind::InvalidData`, keeping the
+/// `Base64Error` as the inner error.
+impl From<Base64Error> for io::Error {
+    fn from(error: Base64Error) -> io::Error {
+        io::Error::new(io::ErrorKind::InvalidData, error)
+    }
+}
+
+#[derive(Debug, PartialEq)]
 pub enum Flavour {
     Base64Standard,
     Base64Url
+}
+
+impl Default for Flavour {
+    /// The flavour used by the functions which do not take one: [`Flavour::Base64Standard`].
+    fn default() -> Flavour {
+        Flavour::Base64Standard
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn when_as_http_should_hide_the_details_of_decode_errors() {
+        let error = crate::try_decode(b"TW!u").unwrap_err();
+        assert_eq!((400, "Invalid base64 character".to_string()), error.as_http());
+        let error = crate::try_decode(b"TWE").unwrap_err();
+        assert_eq!((400, "Invalid base64 padding".to_string()), error.as_http());
+        let error = crate::base64_decode("/w==".to_string(), &Flavour::Base64Standard).unwrap_err();
+        assert_eq!((400, "Decoded data is not valid UTF-8".to_string()), error.as_http());
+        assert_eq!((500, "Internal server error".to_string()), Base64Error::new("Write failed").as_http());
+    }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn when_input_exceeds_limits_or_is_cut_as_http_should_be_a_client_error() {
+        let too_large = (400, "Base64 input too large".to_string());
+        assert_eq!(too_large, crate::base64_decode_max(b"TWFuTWFu", 1).unwrap_err().as_http());
+        assert_eq!(too_large, crate::base64_decode_budgeted(b"TWFuTWFu", 1).unwrap_err().as_http());
+        let engine = crate::Base64Engine::standard().with_max_line_length(4);
+        assert_eq!(too_large, engine.decode(b"TWFuTWFu").unwrap_err().as_http());
+        let invalid_length = (400, "Invalid base64 length".to_string());
+        let res: Result<[u8; 16], Base64Error> = crate::decode_fixed(b"TWFu");
+        assert_eq!(invalid_length, res.unwrap_err().as_http());
+        let header = crate::base64_encode_bytes(&[5, b'M'], &Flavour::default());
+        assert_eq!(invalid_length, crate::decode_with_length_header(&header).unwrap_err().as_http());
+        let header = crate::base64_encode_bytes(&[0x80], &Flavour::default());
+        assert_eq!(invalid_length, crate::decode_framed_multi(&header).unwrap_err().as_http());
+        let res: Result<Vec<u8>, Base64Error> = crate::Base64DecodeIter::new("TWFuT".bytes()).collect();
+        assert_eq!(invalid_length, res.unwrap_err().as_http());
+        let invalid_padding = (400, "Invalid base64 padding".to_string());
+        assert_eq!(invalid_padding, crate::base64_decode_lines(["TWFu", "TW"]).unwrap_err().as_http());
+        let res: Result<Vec<u8>, Base64Error> = crate::Base64DecodeIter::new("TWFuTW".bytes()).collect();
+        assert_eq!(invalid_padding, res.unwrap_err().as_http());
+    }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn when_the_text_around_the_base64_is_malformed_as_http_should_be_a_client_error() {
+        let malformed = (400, "Malformed input".to_string());
+        assert_eq!(malformed, crate::decode_csv_column("TWFu,TWE=", ',', 2).unwrap_err().as_http());
+        assert_eq!(malformed, crate::base64_decode_framed("TWFu", "[", "]").unwrap_err().as_http());
+        assert_eq!(malformed, crate::decode_content_transfer("uuencode", b"TWFu").unwrap_err().as_http());
+        let invalid_character = (400, "Invalid base64 character".to_string());
+        assert_eq!(invalid_character, crate::base64url_decode_percent("TW%2").unwrap_err().as_http());
+        assert_eq!(invalid_character, crate::decode_from_json_string("\\qTWFu").unwrap_err().as_http());
+        assert_eq!(invalid_character, crate::decode_content_transfer("quoted-printable", b"=ZZ").unwrap_err().as_http());
+    }
+
+    #[test]
+    fn when_decode_error_converts_should_keep_its_kind() {
+        let error = Base64Error::from(DecodeError::InvalidByte { position: 3, byte: b'!' });
+        assert_eq!("Invalid character 0x21 at position 3", error.msg);
+        assert_eq!(Some(DecodeError::InvalidByte { position: 3, byte: b'!' }), error.decode_error);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,117 @@
+use crate::constants::{BASE_64_ENCODING_CHARS, BASE_64_ENCODING_CHARS_URL, PADDING_CHAR};
+use crate::errors::{Base64Error, DecodeError, Flavour};
+use crate::{bytes_encode_trio, decode_calc_byte_size, decode_into, encoded_len};
+
+/// Encodes a byte array with the alphabet of the default flavour into an array of exactly `M` characters,
+/// where `M` must be `encoded_len(N)`, which is checked at compile time. Being a `const fn`
+/// without allocations it can also build constants:
+///
+/// ```
+/// use simple_base64::encode_fixed;
+///
+/// const MAN: [u8; 4] = encode_fixed(b"Man");
+/// assert_eq!(b"TWFu", &MAN);
+/// ```
+///
+/// A wrong output size does not compile:
+///
+/// ```compile_fail
+/// let wrong: [u8; 5] = simple_base64::encode_fixed(b"Man");
+/// ```
+pub const fn encode_fixed<const N: usize, const M: usize>(input: &[u8; N]) -> [u8; M] {
+    encode_fixed_with(input, BASE_64_ENCODING_CHARS)
+}
+
+/// [`encode_fixed`] with the URL safe alphabet.
+pub const fn encode_fixed_url<const N: usize, const M: usize>(input: &[u8; N]) -> [u8; M] {
+    encode_fixed_with(input, BASE_64_ENCODING_CHARS_URL)
+}
+
+const fn encode_fixed_with<const N: usize, const M: usize>(input: &[u8; N], table: &[u8]) -> [u8; M] {
+    const { assert!(M == encoded_len(N), "output size M must be encoded_len(N)") };
+    let mut res = [PADDING_CHAR; M];
+    let mut i = 0;
+    while i < N {
+        let remaining = N - i;
+        let trio = [input[i], if remaining > 1 { input[i + 1] } else { 0 }, if remaining > 2 { input[i + 2] } else { 0 }];
+        let quartet = bytes_encode_trio(&trio);
+        let chars = if remaining > 2 { 4 } else { remaining + 1 };
+        let mut j = 0;
+        while j < chars {
+            res[i / 3 * 4 + j] = table[quartet[j]];
+            j += 1;
+        }
+        i += 3;
+    }
+    res
+}
+
+/// Decodes base64 of the default flavour into a stack array, without any heap allocation. Fails unless the
+/// input decodes to exactly `N` bytes, e.g. a 16 byte identifier encoded in 24 characters.
+pub fn decode_fixed<const N: usize>(input: &[u8]) -> Result<[u8; N], Base64Error> {
+    decode_fixed_with(input, &Flavour::default())
+}
+
+/// [`decode_fixed`] with the URL safe alphabet.
+pub fn decode_fixed_url<const N: usize>(input: &[u8]) -> Result<[u8; N], Base64Error> {
+    decode_fixed_with(input, &Flavour::Base64Url)
+}
+
+fn decode_fixed_with<const N: usize>(input: &[u8], flavour: &Flavour) -> Result<[u8; N], Base64Error> {
+    let decoded_length = decode_calc_byte_size(input, flavour.engine());
+    if decoded_length != N || !input.len().is_multiple_of(4) {
+        return Err(Base64Error::malformed(DecodeError::InvalidLength, &format!(
+            "Input of {} characters does not decode to {} bytes", input.len(), N)));
+    }
+    let mut res = [0; N];
+    decode_into(input, flavour.engine(), &mut res)?;
+    Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn when_encode_fixed_should_fill_array() {
+        let man: [u8; 4] = encode_fixed(b"Man");
+        assert_eq!(b"TWFu", &man);
+        let four: [u8; 8] = encode_fixed(b"four");
+        assert_eq!(b"Zm91cg==", &four);
+        let three: [u8; 8] = encode_fixed(b"three");
+        assert_eq!(b"dGhyZWU=", &three);
+        let empty: [u8; 0] = encode_fixed(&[]);
+        assert!(empty.is_empty());
+        let id: [u8; 24] = encode_fixed(&decode_fixed::<16>("AAECAwQFBgcICQoLDA0ODw==".as_bytes()).unwrap());
+        assert_eq!("AAECAwQFBgcICQoLDA0ODw==".as_bytes(), id);
+    }
+
+    #[test]
+    fn when_decode_fixed_should_fill_array() {
+        let id: [u8; 16] = decode_fixed("AAECAwQFBgcICQoLDA0ODw==".as_bytes()).unwrap();
+        let expected: Vec<u8> = (0..16).collect();
+        assert_eq!(expected.as_slice(), id);
+        let man: [u8; 3] = decode_fixed("TWFu".as_bytes()).unwrap();
+        assert_eq!("Man".as_bytes(), man);
+    }
+
+    #[test]
+    fn when_decode_fixed_length_mismatch_should_fail() {
+        let res: Result<[u8; 16], Base64Error> = decode_fixed("AAECAwQFBgcICQoLDA0O".as_bytes());
+        assert_eq!("Input of 20 characters does not decode to 16 bytes", res.unwrap_err().msg);
+        let res: Result<[u8; 2], Base64Error> = decode_fixed("TWFu".as_bytes());
+        assert!(res.is_err());
+        let res: Result<[u8; 16], Base64Error> = decode_fixed("AAECAwQFBgcI!QoLDA0ODw==".as_bytes());
+        assert_eq!("Invalid character 0x21 at position 12", res.unwrap_err().msg);
+    }
+
+    #[test]
+    fn when_url_variants_should_use_the_url_safe_alphabet() {
+        let encoded: [u8; 4] = encode_fixed_url(&[0xfb, 0xff, 0xbf]);
+        assert_eq!(b"-_-_", &encoded);
+        let decoded: [u8; 3] = decode_fixed_url(&encoded).unwrap();
+        assert_eq!([0xfb, 0xff, 0xbf], decoded);
+        let res: Result<[u8; 3], Base64Error> = decode_fixed_url(b"+/+/");
+        assert!(res.is_err());
+    }
+}
@@ -0,0 +1,112 @@
+use crate::engine::URL_SAFE_NO_PAD;
+use crate::errors::{Base64Error, DecodeError};
+
+/// Encodes the big endian bytes of a number in URL safe base64 without padding, a compact form
+/// for identifiers in URLs: 11 characters for a `u64`.
+pub fn base64_encode_u64_be(n: u64) -> String {
+    encode_url_safe_no_pad(&n.to_be_bytes())
+}
+
+/// Encodes the big endian bytes of a number in URL safe base64 without padding: 6 characters.
+pub fn base64_encode_u32_be(n: u32) -> String {
+    encode_url_safe_no_pad(&n.to_be_bytes())
+}
+
+/// Decodes a number encoded by [`base64_encode_u64_be`], failing unless exactly 8 bytes come out.
+pub fn base64_decode_u64_be(s: &str) -> Result<u64, Base64Error> {
+    decode_url_safe_no_pad(s).map(u64::from_be_bytes)
+}
+
+/// Decodes a number encoded by [`base64_encode_u32_be`], failing unless exactly 4 bytes come out.
+pub fn base64_decode_u32_be(s: &str) -> Result<u32, Base64Error> {
+    decode_url_safe_no_pad(s).map(u32::from_be_bytes)
+}
+
+/// Encodes the 16 bytes of a UUID in URL safe base64 without padding, the 22 character short
+/// UUID form.
+pub fn encode_uuid(bytes: [u8; 16]) -> String {
+    encode_url_safe_no_pad(&bytes)
+}
+
+/// Decodes a short UUID produced by [`encode_uuid`] back into its 16 bytes.
+pub fn decode_uuid(s: &str) -> Result<[u8; 16], Base64Error> {
+    decode_url_safe_no_pad(s)
+}
+
+/// Encodes e.g. a hash as a short URL slug: URL safe base64 without padding, so the output only
+/// contains `[A-Za-z0-9_-]`.
+pub fn slug_encode(bytes: &[u8]) -> String {
+    encode_url_safe_no_pad(bytes)
+}
+
+/// Decodes a slug produced by [`slug_encode`]. Padding is rejected like any other character
+/// outside of `[A-Za-z0-9_-]`.
+pub fn slug_decode(s: &str) -> Result<Vec<u8>, Base64Error> {
+    URL_SAFE_NO_PAD.decode(s.as_bytes())
+}
+
+fn encode_url_safe_no_pad(bytes: &[u8]) -> String {
+    String::from_utf8(URL_SAFE_NO_PAD.encode(bytes)).expect("the URL safe alphabet is ASCII")
+}
+
+fn decode_url_safe_no_pad<const N: usize>(s: &str) -> Result<[u8; N], Base64Error> {
+    let decoded = URL_SAFE_NO_PAD.decode(s.as_bytes())?;
+    decoded.as_slice().try_into()
+        .map_err(|_| Base64Error::malformed(DecodeError::InvalidLength,
+                                            &format!("Expected {} decoded bytes, found {}", N, decoded.len())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn when_encode_u64_be_should_round_trip() {
+        for n in [0, 1, 255, 1 << 40, 0xfbff_0000_0000_0000, u64::MAX] {
+            let encoded = base64_encode_u64_be(n);
+            assert_eq!(11, encoded.len());
+            assert!(!encoded.contains('='));
+            assert_eq!(n, base64_decode_u64_be(&encoded).unwrap());
+        }
+        assert_eq!("AAAAAAAAAAE", base64_encode_u64_be(1));
+        assert_eq!("__________8", base64_encode_u64_be(u64::MAX));
+    }
+
+    #[test]
+    fn when_encode_u32_be_should_round_trip() {
+        for n in [0, 7, 0xfbff_0000, u32::MAX] {
+            assert_eq!(n, base64_decode_u32_be(&base64_encode_u32_be(n)).unwrap());
+        }
+        assert_eq!("_____w", base64_encode_u32_be(u32::MAX));
+    }
+
+    #[test]
+    fn when_decode_wrong_length_should_fail() {
+        let error = base64_decode_u64_be(&base64_encode_u32_be(7)).unwrap_err();
+        assert_eq!("Expected 8 decoded bytes, found 4", error.msg);
+        assert_eq!(Some(DecodeError::InvalidLength), error.decode_error);
+        assert!(base64_decode_u32_be("AAAAAAAAAAE").is_err());
+        assert!(base64_decode_u32_be("AAAAAA==").is_err());
+    }
+
+    #[test]
+    fn when_encode_uuid_should_round_trip() {
+        // 550e8400-e29b-41d4-a716-446655440000
+        let uuid = [0x55, 0x0e, 0x84, 0x00, 0xe2, 0x9b, 0x41, 0xd4, 0xa7, 0x16, 0x44, 0x66, 0x55, 0x44, 0x00, 0x00];
+        let encoded = encode_uuid(uuid);
+        assert_eq!("VQ6EAOKbQdSnFkRmVUQAAA", encoded);
+        assert_eq!(uuid, decode_uuid(&encoded).unwrap());
+        assert_eq!("Expected 16 decoded bytes, found 15", decode_uuid("VQ6EAOKbQdSnFkRmVUQA").unwrap_err().msg);
+    }
+
+    #[test]
+    fn when_slug_encode_hash_should_give_27_url_safe_characters() {
+        // the size of a SHA-1 hash
+        let hash: Vec<u8> = (0..20u8).map(|i| i.wrapping_mul(53).wrapping_add(0xf9)).collect();
+        let slug = slug_encode(&hash);
+        assert_eq!(27, slug.len());
+        assert!(slug.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'_' || b == b'-'));
+        assert_eq!(hash, slug_decode(&slug).unwrap());
+        assert_eq!("Invalid character 0x3d at position 27", slug_decode(&format!("{}=", slug)).unwrap_err().msg);
+    }
+}
\ No newline at end of file
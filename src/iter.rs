@@ -0,0 +1,178 @@
+use crate::constants::INVALID_CHAR;
+use crate::engine::Base64Engine;
+use crate::errors::{Base64Error, DecodeError, Flavour};
+use crate::{data_after_padding, decode_checked_quartet, invalid_character, truncated};
+
+/// Lazily decodes an iterator of base64 characters, one quartet at a time, so a character stream
+/// can be decoded without collecting it first. Iteration stops after the first error.
+///
+/// ```
+/// use simple_base64::Base64DecodeIter;
+///
+/// let decoded: Result<Vec<u8>, _> = Base64DecodeIter::new("TWFuTWE=".bytes()).collect();
+/// assert_eq!("ManMa".as_bytes(), decoded.unwrap().as_slice());
+/// ```
+pub struct Base64DecodeIter<I: Iterator<Item = u8>> {
+    input: I,
+    engine: &'static Base64Engine,
+    position: usize,
+    decoded: [u8; 3],
+    decoded_len: usize,
+    next: usize,
+    finished: bool,
+}
+
+impl<I: Iterator<Item = u8>> Base64DecodeIter<I> {
+    /// Decodes with the default flavour.
+    pub fn new(input: I) -> Base64DecodeIter<I> {
+        Base64DecodeIter::with_flavour(input, &Flavour::default())
+    }
+
+    pub fn with_flavour(input: I, flavour: &Flavour) -> Base64DecodeIter<I> {
+        Base64DecodeIter { input, engine: flavour.engine(), position: 0, decoded: [0; 3], decoded_len: 0,
+            next: 0, finished: false }
+    }
+
+    /// Reads and decodes the next quartet. Returns false at the end of the input.
+    fn fill(&mut self) -> Result<bool, Base64Error> {
+        let table = self.engine.decode_table();
+        let start = self.position;
+        let mut chars = [0; 4];
+        let mut converted = [0; 4];
+        let mut count = 0;
+        while count < 4 {
+            match self.input.next() {
+                Some(b) => {
+                    if table[b as usize] == INVALID_CHAR {
+                        return Err(invalid_character(b, self.position));
+                    }
+                    chars[count] = b;
+                    converted[count] = table[b as usize];
+                    count += 1;
+                    self.position += 1;
+                }
+                None => break
+            }
+        }
+        if count == 0 {
+            return Ok(false);
+        }
+        if count < 4 {
+            return Err(truncated(start, count));
+        }
+        let (decoded, size) = decode_checked_quartet(converted, true)
+            .map_err(|i| invalid_character(chars[i], start + i))?;
+        if size < 3 {
+            // a padded group must be the last one
+            if let Some(b) = self.input.next() {
+                if table[b as usize] == INVALID_CHAR {
+                    return Err(invalid_character(b, self.position));
+                }
+                return Err(data_after_padding(self.position - 1));
+            }
+            self.finished = true;
+        }
+        self.decoded = decoded;
+        self.decoded_len = size;
+        self.next = 0;
+        Ok(true)
+    }
+}
+
+/// Decodes an iterator of characters with the default flavour through [`Base64DecodeIter`]. The
+/// input is never collected, so a corrupt stream fails at its first invalid character without
+/// reading the rest.
+pub fn base64_decode_iter<I: Iterator<Item = u8>>(iter: I) -> Result<Vec<u8>, Base64Error> {
+    Base64DecodeIter::new(iter).collect()
+}
+
+/// Decodes characters handed over as `char`s with the default flavour, without building a string
+/// first. Fails with the position of the first non ASCII character, if any.
+pub fn base64_decode_chars(chars: &[char]) -> Result<Vec<u8>, Base64Error> {
+    if let Some(position) = chars.iter().position(|c| !c.is_ascii()) {
+        let mut utf8 = [0; 4];
+        // reported as the first byte of its UTF-8 encoding, which is never in an alphabet
+        let byte = chars[position].encode_utf8(&mut utf8).as_bytes()[0];
+        return Err(Base64Error::malformed(DecodeError::InvalidByte { position, byte },
+                                          &format!("Non ASCII character '{}' at position {}", chars[position], position)));
+    }
+    base64_decode_iter(chars.iter().map(|c| *c as u8))
+}
+
+impl<I: Iterator<Item = u8>> Iterator for Base64DecodeIter<I> {
+    type Item = Result<u8, Base64Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.next < self.decoded_len {
+                self.next += 1;
+                return Some(Ok(self.decoded[self.next - 1]));
+            }
+            if self.finished {
+                return None;
+            }
+            match self.fill() {
+                Ok(true) => continue,
+                Ok(false) => {
+                    self.finished = true;
+                    return None;
+                }
+                Err(error) => {
+                    self.finished = true;
+                    return Some(Err(error));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode(input: &str) -> Result<Vec<u8>, Base64Error> {
+        Base64DecodeIter::with_flavour(input.bytes(), &Flavour::Base64Standard).collect()
+    }
+
+    #[test]
+    fn when_decode_iter_should_handle_final_padded_group() {
+        assert_eq!("ManMa".as_bytes(), decode("TWFuTWE=").unwrap().as_slice());
+        assert_eq!("ManM".as_bytes(), decode("TWFuTQ==").unwrap().as_slice());
+        assert_eq!("ManMan".as_bytes(), decode("TWFuTWFu").unwrap().as_slice());
+        assert!(decode("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn when_decode_iter_malformed_should_stop_with_error() {
+        assert_eq!("Invalid character 0x21 at position 5", decode("TWFuT!E=").unwrap_err().msg);
+        assert_eq!("Truncated input: final group at position 4 has 2 characters", decode("TWFuTW").unwrap_err().msg);
+        assert_eq!("Padded group ending at position 3 is followed by more data", decode("TQ==TWFu").unwrap_err().msg);
+        assert!(decode("T===").is_err());
+        let mut iter = Base64DecodeIter::new("TWFu!".bytes());
+        assert_eq!(3, iter.by_ref().take(3).filter(|r| r.is_ok()).count());
+        assert!(iter.next().unwrap().is_err());
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn when_base64_decode_iter_meets_a_bad_byte_should_stop_reading() {
+        let mut read = 0;
+        // endless input: decoding only returns if it stops at the bad byte
+        let input = "TWFuTWFu".bytes().chain(std::iter::once(b'!')).chain(std::iter::repeat(b'A')).inspect(|_| read += 1);
+        let res = base64_decode_iter(input);
+        assert_eq!("Invalid character 0x21 at position 8", res.unwrap_err().msg);
+        assert_eq!(9, read);
+        assert_eq!("ManMa".as_bytes(), base64_decode_iter("TWFuTWE=".bytes()).unwrap());
+    }
+
+    #[test]
+    fn when_base64_decode_chars_should_reject_non_ascii() {
+        let chars: Vec<char> = "TWFuTWE=".chars().collect();
+        assert_eq!("ManMa".as_bytes(), base64_decode_chars(&chars).unwrap());
+        let chars: Vec<char> = "TWFué".chars().collect();
+        let error = base64_decode_chars(&chars).unwrap_err();
+        assert_eq!("Non ASCII character 'é' at position 4", error.msg);
+        assert_eq!(Some(DecodeError::InvalidByte { position: 4, byte: 0xc3 }), error.decode_error);
+        assert_eq!("Invalid character 0x21 at position 1", base64_decode_chars(&['T', '!']).unwrap_err().msg);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,52 @@
+use crate::engine::URL_SAFE_NO_PAD;
+use crate::errors::{Base64Error, DecodeError};
+
+/// The decoded header, payload and signature of a JWT.
+pub type JwtSegments = (Vec<u8>, Vec<u8>, Vec<u8>);
+
+/// Splits a JWT (or any JWS in compact form) on `.` and decodes its header, payload and
+/// signature, each base64url without padding. The signature comes back as raw bytes, the header
+/// and payload are usually JSON. Fails unless there are exactly three segments.
+pub fn decode_jwt_segments(token: &str) -> Result<JwtSegments, Base64Error> {
+    let segments: Vec<&str> = token.split('.').collect();
+    let [header, payload, signature] = segments[..] else {
+        return Err(Base64Error::malformed(DecodeError::InvalidFormat,
+                                          &format!("Expected 3 JWT segments, found {}", segments.len())));
+    };
+    Ok((decode_segment(header, "header")?, decode_segment(payload, "payload")?, decode_segment(signature, "signature")?))
+}
+
+fn decode_segment(segment: &str, name: &str) -> Result<Vec<u8>, Base64Error> {
+    URL_SAFE_NO_PAD.decode(segment.as_bytes())
+        .map_err(|error| Base64Error { msg: format!("Invalid JWT {}: {}", name, error.msg), ..error })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOKEN: &str = "eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9.\
+        eyJzdWIiOiIxMjM0NTY3ODkwIiwibmFtZSI6IkpvaG4gRG9lIiwiaWF0IjoxNTE2MjM5MDIyfQ.\
+        SflKxwRJSMeKKF2QT4fwpMeJf36POk6yJV_adQssw5c";
+
+    #[test]
+    fn when_decode_jwt_segments_should_give_header_payload_and_signature() {
+        let (header, payload, signature) = decode_jwt_segments(TOKEN).unwrap();
+        assert_eq!(r#"{"alg":"HS256","typ":"JWT"}"#.as_bytes(), header);
+        assert_eq!(r#"{"sub":"1234567890","name":"John Doe","iat":1516239022}"#.as_bytes(), payload);
+        assert_eq!(32, signature.len());
+        assert_eq!([0x49, 0xf9, 0x4a, 0xc7], signature[..4]);
+    }
+
+    #[test]
+    fn when_jwt_segments_are_wrong_should_fail() {
+        assert_eq!("Expected 3 JWT segments, found 2", decode_jwt_segments("eyJhbGciOiJub25lIn0.e30").unwrap_err().msg);
+        let error = decode_jwt_segments("a.b.c.d").unwrap_err();
+        assert_eq!("Expected 3 JWT segments, found 4", error.msg);
+        assert_eq!(Some(DecodeError::InvalidFormat), error.decode_error);
+        let error = decode_jwt_segments("eyJhbGciOiJub25lIn0.e30=.").unwrap_err();
+        assert_eq!("Invalid JWT payload: Invalid character 0x3d at position 3", error.msg);
+        // the signature of an unsecured JWT is empty
+        assert_eq!(Vec::<u8>::new(), decode_jwt_segments("eyJhbGciOiJub25lIn0.e30.").unwrap().2);
+    }
+}
\ No newline at end of file
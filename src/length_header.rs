@@ -0,0 +1,128 @@
+use crate::errors::{Base64Error, DecodeError, Flavour};
+use crate::{base64_decode_bytes, base64_encode_bytes};
+
+/// Encodes with the default flavour after prefixing the bytes with their length as an unsigned
+/// LEB128 varint, so [`decode_with_length_header`] can detect a truncated payload.
+pub fn encode_with_length_header(bytes: &[u8]) -> Vec<u8> {
+    let mut framed = Vec::with_capacity(bytes.len() + 10);
+    push_length(&mut framed, bytes.len());
+    framed.extend_from_slice(bytes);
+    base64_encode_bytes(&framed, &Flavour::default())
+}
+
+/// Decodes output of [`encode_with_length_header`], failing unless the payload has exactly the
+/// length declared in its header.
+pub fn decode_with_length_header(input: &[u8]) -> Result<Vec<u8>, Base64Error> {
+    let mut decoded = base64_decode_bytes(input, &Flavour::default())?;
+    let (length, header_size) = read_length(&decoded)?;
+    let payload_length = decoded.len() - header_size;
+    if length != payload_length as u64 {
+        return Err(Base64Error::malformed(DecodeError::InvalidLength, &format!(
+            "Length header declares {} bytes, found {}", length, payload_length)));
+    }
+    decoded.drain(..header_size);
+    Ok(decoded)
+}
+
+/// Encodes several items with the default flavour as a single string, each behind its length
+/// like in [`encode_with_length_header`], so [`decode_framed_multi`] recovers their boundaries.
+pub fn encode_framed_multi(items: &[&[u8]]) -> String {
+    let mut framed = Vec::with_capacity(items.iter().map(|item| item.len() + 10).sum());
+    for item in items {
+        push_length(&mut framed, item.len());
+        framed.extend_from_slice(item);
+    }
+    String::from_utf8(base64_encode_bytes(&framed, &Flavour::default())).expect("base64 alphabets are ASCII")
+}
+
+/// Decodes output of [`encode_framed_multi`] back into its items, failing if the last one is cut.
+pub fn decode_framed_multi(input: &[u8]) -> Result<Vec<Vec<u8>>, Base64Error> {
+    let decoded = base64_decode_bytes(input, &Flavour::default())?;
+    let mut items = Vec::new();
+    let mut rest = decoded.as_slice();
+    while !rest.is_empty() {
+        let (length, header_size) = read_length(rest)?;
+        let payload = &rest[header_size..];
+        if length > payload.len() as u64 {
+            return Err(Base64Error::malformed(DecodeError::InvalidLength, &format!(
+                "Item {} declares {} bytes, found {}", items.len(), length, payload.len())));
+        }
+        let (item, remaining) = payload.split_at(length as usize);
+        items.push(item.to_vec());
+        rest = remaining;
+    }
+    Ok(items)
+}
+
+/// Appends `length` as an unsigned LEB128 varint.
+fn push_length(framed: &mut Vec<u8>, length: usize) {
+    let mut length = length as u64;
+    loop {
+        let byte = (length & 0x7f) as u8;
+        length >>= 7;
+        if length == 0 {
+            framed.push(byte);
+            break;
+        }
+        framed.push(byte | 0x80);
+    }
+}
+
+/// Reads the LEB128 length at the start of `bytes`, returning it with the size of the header.
+fn read_length(bytes: &[u8]) -> Result<(u64, usize), Base64Error> {
+    let mut length: u64 = 0;
+    let mut header_size = 0;
+    loop {
+        let byte = match bytes.get(header_size) {
+            Some(byte) if header_size < 10 => *byte,
+            _ => return Err(Base64Error::malformed(DecodeError::InvalidLength, "Malformed length header"))
+        };
+        length |= u64::from(byte & 0x7f) << (7 * header_size);
+        header_size += 1;
+        if byte & 0x80 == 0 {
+            return Ok((length, header_size));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn when_length_header_should_round_trip() {
+        for size in [0, 1, 127, 128, 300, 20_000] {
+            let bytes: Vec<u8> = (0..size).map(|i| (i % 251) as u8).collect();
+            assert_eq!(bytes, decode_with_length_header(&encode_with_length_header(&bytes)).unwrap());
+        }
+        // 300 is 0xac 0x02 in LEB128
+        let encoded = encode_with_length_header(&[0; 300]);
+        assert_eq!(vec![0xac, 0x02], base64_decode_bytes(&encoded, &Flavour::default()).unwrap()[..2]);
+    }
+
+    #[test]
+    fn when_length_header_payload_truncated_should_fail() {
+        let encoded = encode_with_length_header("Assuming Man".as_bytes());
+        let res = decode_with_length_header(&encoded[..12]);
+        assert_eq!("Length header declares 12 bytes, found 8", res.unwrap_err().msg);
+        assert_eq!("Malformed length header", decode_with_length_header(b"").unwrap_err().msg);
+        assert_eq!("Malformed length header", decode_with_length_header(b"gA==").unwrap_err().msg);
+    }
+
+    #[test]
+    fn when_framed_multi_should_round_trip_items_of_different_lengths() {
+        let long: Vec<u8> = (0..300).map(|i| (i % 251) as u8).collect();
+        let items: [&[u8]; 3] = [b"Man", b"", &long];
+        let encoded = encode_framed_multi(&items);
+        assert_eq!(items.to_vec(), decode_framed_multi(encoded.as_bytes()).unwrap());
+        assert_eq!("A01hbgA=", encode_framed_multi(&[b"Man", b""]));
+        assert!(decode_framed_multi(b"").unwrap().is_empty());
+    }
+
+    #[test]
+    fn when_framed_multi_item_truncated_should_fail() {
+        // a header of 3 bytes followed by 2
+        let res = decode_framed_multi(b"A01h");
+        assert_eq!("Item 0 declares 3 bytes, found 2", res.unwrap_err().msg);
+    }
+}
\ No newline at end of file
@@ -1,16 +1,78 @@
+use std::borrow::Cow;
+use std::hash::Hasher;
 use std::{fs, str};
-use std::io::{Error, ErrorKind};
-use std::path::PathBuf;
+use std::io::{self, Error, Read, Write};
+use std::path::Path;
 use std::str::Utf8Error;
 
-use crate::constants::{BASE_64_ENCODING_CHARS, BASE_64_ENCODING_CHARS_URL, CHARS_BASE_64_ENCODING, CHARS_BASE_64_ENCODING_URL, PADDING_CHAR};
-use crate::errors::{Base64Error, Flavour, PaddingError};
+#[cfg(feature = "tokio")]
+pub use crate::async_io::{AsyncBase64Reader, AsyncBase64Writer};
+#[cfg(feature = "bytes")]
+pub use crate::bytes_crate::{decode_bytes_crate, encode_bytes_crate};
+#[cfg(feature = "flate2")]
+pub use crate::compressed::{decode_compressed, encode_compressed};
+#[cfg(feature = "rayon")]
+pub use crate::parallel::{base64_decode_bytes_parallel, base64_encode_bytes_parallel};
+#[cfg(feature = "bytemuck")]
+pub use crate::pod::{decode_pod_slice, encode_pod_slice};
+#[cfg(feature = "getrandom")]
+pub use crate::salted::{base64_decode_salted, base64_encode_salted};
+#[cfg(feature = "smallvec")]
+pub use crate::small::base64_decode_smallvec;
+use crate::constants::{INVALID_CHAR, PADDING_CHAR, PADDING_CODE, UTF8_BOM};
+pub use crate::decoder::Decoder;
+pub use crate::engine::{Base64Engine, LineEnding, PostProcess};
+use crate::engine::{MIXED, MIXED_NO_PAD, URL_SAFE_NO_PAD};
+pub use crate::errors::{Base64Error, DecodeError, Flavour, PaddingError};
+pub use crate::fixed::{decode_fixed, decode_fixed_url, encode_fixed, encode_fixed_url};
+pub use crate::ids::{base64_decode_u32_be, base64_decode_u64_be, base64_encode_u32_be, base64_encode_u64_be, decode_uuid,
+                        encode_uuid, slug_decode, slug_encode};
+pub use crate::iter::{base64_decode_chars, base64_decode_iter, Base64DecodeIter};
+pub use crate::jwt::{decode_jwt_segments, JwtSegments};
+pub use crate::length_header::{decode_framed_multi, decode_with_length_header, encode_framed_multi,
+                                encode_with_length_header};
+pub use crate::mime::decode_content_transfer;
+pub use crate::pipe::{pipe_decode, pipe_decode_io, pipe_encode, pipe_encode_io};
+pub use crate::reader::Base64Reader;
+use crate::reader::StreamDecoder;
+pub use crate::shards::{decode_shards, shard_encoded};
+pub use crate::writer::Base64Writer;
 use crate::Flavour::{Base64Standard, Base64Url};
 
+#[cfg(feature = "tokio")]
+mod async_io;
+#[cfg(feature = "bytes")]
+mod bytes_crate;
+#[cfg(feature = "flate2")]
+mod compressed;
 mod constants;
+pub mod debug;
+mod decoder;
+mod engine;
 mod errors;
+mod fixed;
+mod ids;
+mod iter;
+mod jwt;
+mod length_header;
+pub mod low_level;
+mod mime;
+#[cfg(feature = "rayon")]
+mod parallel;
+mod pipe;
+#[cfg(feature = "bytemuck")]
+mod pod;
+mod reader;
+#[cfg(feature = "getrandom")]
+mod salted;
+mod shards;
+#[cfg(feature = "smallvec")]
+mod small;
+#[cfg(feature = "url-safe-default")]
+pub mod url_safe;
+mod writer;
 
-/**
+/*
  * The "base64" base encoding specified by <a
  * href="http://tools.ietf.org/html/rfc4648#section-4">RFC 4648 section 4</a>, Base 64 Encoding.
  * (This is the same as the base 64 encoding from <a
@@ -24,6 +86,27 @@ mod errors;
  * Encoded Data. Line feeds may be added using {@link #withSeparator(String, int)}.
  */
 
+/// Encodes bytes with the default flavour (see [`Flavour::default`]) and padding.
+///
+/// Together with [`try_decode`] this is the recommended entry point: it never panics, whatever
+/// the input, which makes it safe to use in servers built with `panic = "abort"`.
+pub fn try_encode(bytes: &[u8]) -> Result<Vec<u8>, Base64Error> {
+    try_encode_with(bytes, &Flavour::default())
+}
+
+pub(crate) fn try_encode_with(bytes: &[u8], flavour: &Flavour) -> Result<Vec<u8>, Base64Error> {
+    if bytes.len() > usize::MAX / 4 * 3 {
+        return Err(Base64Error::new("Input too large to encode"));
+    }
+    Ok(base64_encode_bytes(bytes, flavour))
+}
+
+/// Decodes padded base64 of the default flavour and never panics: malformed input of any kind (invalid
+/// characters, bad length, misplaced padding) is reported as an error.
+pub fn try_decode(bytes: &[u8]) -> Result<Vec<u8>, Base64Error> {
+    base64_decode_bytes(bytes, &Flavour::default())
+}
+
 pub fn base64_encode_standard(str: String) -> Result<String, Utf8Error> {
     base64_encode(str, &Base64Standard)
 }
@@ -36,7 +119,27 @@ pub fn base64_encode(str: String, flavour: &Flavour) -> Result<String, Utf8Error
     let bytes = str.as_bytes();
     let vec = base64_encode_bytes(bytes, flavour);
     let res = str::from_utf8(&vec)?;
-    return Ok(res.to_string());
+    Ok(res.to_string())
+}
+
+/// Encodes with the default flavour into a `String` sized up front, with a single allocation and
+/// no UTF-8 validation of the output. Works on any slice, e.g. a memory mapped file.
+pub fn base64_encode_slice_to_string(bytes: &[u8]) -> String {
+    let encoded = encode_bytes(bytes, Flavour::default().engine());
+    // SAFETY: the built in alphabets and the padding are ASCII, so the bytes are valid UTF-8
+    unsafe { String::from_utf8_unchecked(encoded) }
+}
+
+/// Appends the encoding of `bytes` with the default flavour to `out`, reserving the space and
+/// encoding straight into it, so building a large document takes no temporary buffer per call.
+pub fn base64_encode_append_str(bytes: &[u8], out: &mut String) {
+    let engine = Flavour::default().engine();
+    let start = out.len();
+    out.reserve(encoded_len(bytes.len()));
+    // SAFETY: the zeros added by resize are then overwritten with ASCII, so `out` stays valid UTF-8
+    let buf = unsafe { out.as_mut_vec() };
+    buf.resize(start + encode_calc_byte_size(bytes, engine.pad_char().is_some()), 0);
+    encode_into(bytes, engine, &mut buf[start..]);
 }
 
 pub fn base64_decode(str: String, flavour: &Flavour) -> Result<String, Base64Error> {
@@ -47,64 +150,275 @@ pub fn base64_decode(str: String, flavour: &Flavour) -> Result<String, Base64Err
             match str::from_utf8(&decoded) {
                 Ok(s) => { Ok(s.to_string()) }
                 Err(error) => {
-                    Err(Base64Error { msg: "UTF8 encoding failed".to_string(), utf8_error: Some(error) })
+                    Err(Base64Error { msg: "UTF8 encoding failed".to_string(), utf8_error: Some(error), decode_error: None })
                 }
             }
         }
-        Err(_) => {
-            Err(Base64Error { msg: "Decoding failed".to_string(), utf8_error: None })
+        Err(error) => {
+            Err(Base64Error { msg: format!("Decoding failed: {}", error.msg), ..error })
+        }
+    }
+}
+
+/// Decodes and re-encodes with the engine, so values differing only in their non-canonical
+/// trailing bits (like "TR==" and "TQ==") end up with the same representation.
+pub fn canonicalize(input: &str, engine: &Base64Engine) -> Result<String, Base64Error> {
+    let decoded = engine.decode(input.as_bytes())?;
+    String::from_utf8(engine.encode(&decoded))
+        .map_err(|error| Base64Error { msg: "UTF8 encoding failed".to_string(), utf8_error: Some(error.utf8_error()), decode_error: None })
+}
+
+/// Decodes base64url which is still percent-encoded, e.g. `%2D` instead of `-`, as found in URLs.
+pub fn base64url_decode_percent(input: &str) -> Result<Vec<u8>, Base64Error> {
+    let bytes = input.as_bytes();
+    let mut unescaped = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let high = bytes.get(i + 1).and_then(|b| hex_value(*b));
+            let low = bytes.get(i + 2).and_then(|b| hex_value(*b));
+            match (high, low) {
+                (Some(high), Some(low)) => unescaped.push(high << 4 | low),
+                _ => return Err(Base64Error::malformed(DecodeError::InvalidByte { position: i, byte: b'%' },
+                                                      &format!("Malformed percent sequence at position {}", i)))
+            }
+            i += 3;
+        } else {
+            unescaped.push(bytes[i]);
+            i += 1;
+        }
+    }
+    base64_decode_bytes(&unescaped, &Base64Url)
+}
+
+/// Decodes base64 taken from a JSON string, with or without its quotes, accepting both alphabets
+/// like [`base64_decode_mixed`]. JSON escapes are resolved first, notably `\/` which many
+/// serializers emit for the `/` of the standard alphabet; escaped line breaks, left by wrapped
+/// values, are dropped.
+pub fn decode_from_json_string(s: &str) -> Result<Vec<u8>, Base64Error> {
+    let s = s.strip_prefix('"').and_then(|inner| inner.strip_suffix('"')).unwrap_or(s);
+    let bytes = s.as_bytes();
+    let mut unescaped = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] != b'\\' {
+            unescaped.push(bytes[i]);
+            i += 1;
+            continue;
         }
+        let escaped = match bytes.get(i + 1) {
+            Some(b'n' | b'r') => None,
+            Some(b'/') => Some(b'/'),
+            Some(b'\\') => Some(b'\\'),
+            Some(b'"') => Some(b'"'),
+            Some(b'b') => Some(0x08),
+            Some(b'f') => Some(0x0c),
+            Some(b't') => Some(b'\t'),
+            Some(b'u') => {
+                let code = bytes.get(i + 2..i + 6)
+                    .and_then(|hex| hex.iter().try_fold(0u32, |code, b| hex_value(*b).map(|d| code << 4 | d as u32)))
+                    .filter(|code| *code < 0x80)
+                    .ok_or_else(|| Base64Error::malformed(DecodeError::InvalidByte { position: i, byte: b'\\' },
+                                                           &format!("Malformed or non ASCII \\u escape at position {}", i)))?;
+                i += 4;
+                Some(code as u8)
+            }
+            _ => return Err(Base64Error::malformed(DecodeError::InvalidByte { position: i, byte: b'\\' },
+                                                  &format!("Malformed JSON escape at position {}", i)))
+        };
+        unescaped.extend(escaped);
+        i += 2;
+    }
+    MIXED.decode(&unescaped)
+}
+
+/// Decodes base64url without padding (RFC 4648 section 5), the convention of JWT and JOSE. Padded
+/// input is rejected.
+pub fn base64url_decode_nopad(input: &[u8]) -> Result<Vec<u8>, Base64Error> {
+    URL_SAFE_NO_PAD.decode(input)
+}
+
+pub(crate) fn hex_value(b: u8) -> Option<u8> {
+    (b as char).to_digit(16).map(|d| d as u8)
+}
+
+/// Decodes the base64 (default flavour) in column `index` (zero based) of a separated row, e.g. a CSV line.
+/// The cell is trimmed first; an empty cell decodes to no bytes.
+pub fn decode_csv_column(row: &str, sep: char, index: usize) -> Result<Vec<u8>, Base64Error> {
+    match row.split(sep).nth(index) {
+        Some(cell) => base64_decode_bytes(cell.trim().as_bytes(), &Flavour::default()),
+        None => Err(Base64Error::malformed(DecodeError::InvalidFormat, &format!("Column {} out of range", index)))
     }
 }
 
 pub fn base64_encode_file_str_standard(path_str: &str) -> Result<Vec<u8>, Error> {
-    return base64_encode_file_str(path_str, &Base64Standard)
+    base64_encode_file_str(path_str, &Base64Standard)
 }
 
 pub fn base64_encode_file_str_url(path_str: &str) -> Result<Vec<u8>, Error> {
-    return base64_encode_file_str(path_str, &Base64Url)
+    base64_encode_file_str(path_str, &Base64Url)
 }
 
+/// Kept for compatibility: [`base64_encode_file`] accepts a `&str` directly.
 pub fn base64_encode_file_str(path_str: &str, flavour: &Flavour) -> Result<Vec<u8>, Error> {
-    let path = PathBuf::from(path_str);
-    base64_encode_file(path, flavour)
+    base64_encode_file(path_str, flavour)
 }
 
-pub fn base64_encode_file(path: PathBuf, flavour: &Flavour) -> Result<Vec<u8>, Error> {
+pub fn base64_encode_file(path: impl AsRef<Path>, flavour: &Flavour) -> Result<Vec<u8>, Error> {
     let data = fs::read(path)?;
     let encoded = base64_encode_bytes(&data, flavour);
     Ok(encoded)
 }
 
-pub fn base64_encode_to_file(path: PathBuf, target_path: PathBuf, flavour: &Flavour) -> Result<usize, Error> {
-    let res = base64_encode_file(path, flavour)?;
-    let len = res.len();
-    fs::write(target_path, res)?;
-    Ok(len)
+pub fn base64_encode_to_file(path: impl AsRef<Path>, target_path: impl AsRef<Path>, flavour: &Flavour) -> Result<usize, Error> {
+    base64_encode_to_file_with_progress(path, target_path, flavour, |_, _| {})
+}
+
+/// Like [`base64_encode_to_file`], calling `progress` with the number of bytes encoded so far and
+/// the file size after each chunk of 3 KiB, e.g. to drive a progress bar. An empty file reports
+/// `(0, 0)` once.
+pub fn base64_encode_to_file_with_progress(path: impl AsRef<Path>, target_path: impl AsRef<Path>, flavour: &Flavour,
+                                           progress: impl FnMut(usize, usize)) -> Result<usize, Error> {
+    let data = fs::read(path)?;
+    encode_to_writer(&data, flavour.engine(), fs::File::create(target_path)?, progress)
+}
+
+/// Encodes with the default flavour into `writer` in chunks, without holding the whole encoded
+/// output in memory. Returns the number of characters written. Any sink works: a file, a socket,
+/// a compressor.
+pub fn base64_encode_to_writer<W: Write>(bytes: &[u8], writer: W) -> Result<usize, Error> {
+    encode_to_writer(bytes, Flavour::default().engine(), writer, |_, _| {})
+}
+
+/// Like [`base64_encode_to_writer`], calling `progress` with the number of bytes encoded so far and
+/// the total after each chunk of 3 KiB. Empty input reports `(0, 0)` once.
+pub fn base64_encode_to_writer_with_progress<W: Write>(bytes: &[u8], writer: W, progress: impl FnMut(usize, usize))
+                                                       -> Result<usize, Error> {
+    encode_to_writer(bytes, Flavour::default().engine(), writer, progress)
+}
+
+/// Encodes with the default flavour to stdout, which is locked once for the whole output.
+pub fn base64_encode_to_stdout(bytes: &[u8]) -> Result<(), Error> {
+    base64_encode_to_writer(bytes, io::stdout().lock()).map(|_| ())
+}
+
+/// Encodes with the default flavour and hands the output to `sink` in slices of at most
+/// `chunk_size` characters as they are produced, never holding more than one chunk.
+pub fn base64_encode_streaming(bytes: &[u8], chunk_size: usize, mut sink: impl FnMut(&[u8])) {
+    let engine = Flavour::default().engine();
+    let chunk_size = chunk_size.max(1);
+    // whole trios per input chunk, so only the last chunk can be padded
+    let input_chunk = (chunk_size / 4).max(1) * 3;
+    let mut buffer = vec![0; encoded_len(input_chunk)];
+    for chunk in bytes.chunks(input_chunk) {
+        let size = encode_calc_byte_size(chunk, engine.pad_char().is_some());
+        encode_into(chunk, engine, &mut buffer[..size]);
+        for piece in buffer[..size].chunks(chunk_size) {
+            sink(piece);
+        }
+    }
+}
+
+fn encode_to_writer<W: Write>(bytes: &[u8], engine: &Base64Engine, mut writer: W,
+                              mut progress: impl FnMut(usize, usize)) -> Result<usize, Error> {
+    const CHUNK: usize = 3 * 1024;
+    let mut buffer = [0; encoded_len(CHUNK)];
+    let mut written = 0;
+    if bytes.is_empty() {
+        // there is no chunk to report on, but the caller still learns that encoding is done
+        progress(0, 0);
+    }
+    for (i, chunk) in bytes.chunks(CHUNK).enumerate() {
+        // every chunk but the last is a multiple of 3, so padding only ends the final one
+        let size = encode_calc_byte_size(chunk, engine.pad_char().is_some());
+        encode_into(chunk, engine, &mut buffer[..size]);
+        writer.write_all(&buffer[..size])?;
+        written += size;
+        progress(i * CHUNK + chunk.len(), bytes.len());
+    }
+    writer.flush()?;
+    Ok(written)
 }
 
-pub fn base64_decode_from_file_standard(source_path: PathBuf, target_path: PathBuf) -> Result<usize, Error> {
+pub fn base64_decode_from_file_standard(source_path: impl AsRef<Path>, target_path: impl AsRef<Path>) -> Result<usize, Error> {
     base64_decode_from_file(source_path, target_path, &Base64Standard)
 }
 
-pub fn base64_decode_from_file_url(source_path: PathBuf, target_path: PathBuf) -> Result<usize, Error> {
+pub fn base64_decode_from_file_url(source_path: impl AsRef<Path>, target_path: impl AsRef<Path>) -> Result<usize, Error> {
     base64_decode_from_file(source_path, target_path, &Base64Url)
 }
 
-pub fn base64_decode_from_file(source_path: PathBuf, target_path: PathBuf, flavour: &Flavour) -> Result<usize, Error> {
+pub fn base64_decode_from_file(source_path: impl AsRef<Path>, target_path: impl AsRef<Path>, flavour: &Flavour) -> Result<usize, Error> {
     let data = fs::read(source_path)?;
-    let bytes = data.as_slice();
-    let decoded_res = base64_decode_bytes(bytes, flavour);
-    match decoded_res {
-        Ok(decoded) => {
-            let len = decoded.len();
-            fs::write(target_path, decoded)?;
-            Ok(len)
+    let bytes = strip_bom(&data);
+    decoded_len_checked(bytes, flavour.engine())?;
+    write_decoded(bytes, flavour.engine(), fs::File::create(target_path)?)
+}
+
+/// Checks that a file holds valid base64 of the flavour, e.g. for a linter, reading it in chunks
+/// and decoding nothing. ASCII whitespace is skipped when `allow_whitespace`. Errors give the byte
+/// offset in the file.
+pub fn validate_base64_file(path: impl AsRef<Path>, flavour: &Flavour, allow_whitespace: bool) -> Result<(), Base64Error> {
+    let engine = flavour.engine();
+    let read_error = |error: Error| Base64Error::new(&format!("Read failed: {}", error));
+    let mut file = fs::File::open(path).map_err(read_error)?;
+    let mut chunk = [0; 8192];
+    let (mut offset, mut data_length, mut pads) = (0, 0, 0);
+    loop {
+        let read = file.read(&mut chunk).map_err(read_error)?;
+        if read == 0 {
+            break;
         }
-        Err(_) => {
-            Err(Error::new(ErrorKind::InvalidInput, "Padding error occurred."))
+        for b in &chunk[..read] {
+            let code = engine.decode_table()[*b as usize];
+            if allow_whitespace && b.is_ascii_whitespace() {
+                offset += 1;
+                continue;
+            }
+            if code == PADDING_CODE {
+                pads += 1;
+            } else if code == INVALID_CHAR || pads > 0 {
+                // data after padding is as wrong as a character outside of the alphabet
+                return Err(invalid_character(*b, offset));
+            } else {
+                data_length += 1;
+            }
+            offset += 1;
         }
     }
+    if pads == 0 || data_length % 4 == 1 {
+        return check_final_group(data_length, engine.pad_char().is_some());
+    }
+    let expected = (4 - data_length % 4) % 4;
+    if pads != expected {
+        return Err(Base64Error::malformed(DecodeError::InvalidPadding, &format!(
+            "Expected {} padding characters after {} data characters, found {}", expected, data_length, pads)));
+    }
+    Ok(())
+}
+
+/// Decodes base64 of the default flavour into `writer`, e.g. a file or a hashing writer, in
+/// chunks without an intermediate `Vec`. The input is validated before anything is written.
+/// Returns the number of bytes written.
+pub fn base64_decode_to_writer<W: Write>(input: &[u8], writer: W) -> Result<usize, Base64Error> {
+    let engine = Flavour::default().engine();
+    let input = strip_bom(input);
+    decoded_len_checked(input, engine)?;
+    write_decoded(input, engine, writer).map_err(|e| Base64Error::new(&format!("Write failed: {}", e)))
+}
+
+/// Writes the decoded bytes of input already checked by `decoded_len_checked`.
+fn write_decoded<W: Write>(input: &[u8], engine: &Base64Engine, mut writer: W) -> Result<usize, Error> {
+    const CHUNK: usize = 4 * 1024;
+    let mut buffer = [0; CHUNK / 4 * 3];
+    let mut written = 0;
+    for chunk in input.chunks(CHUNK) {
+        let size = decode_into(chunk, engine, &mut buffer)?;
+        writer.write_all(&buffer[..size])?;
+        written += size;
+    }
+    writer.flush()?;
+    Ok(written)
 }
 
 pub fn base64_encode_bytes_standard(bytes: &[u8]) -> Vec<u8> {
@@ -116,108 +430,564 @@ pub fn base64_encode_bytes_url(bytes: &[u8]) -> Vec<u8> {
 }
 
 pub fn base64_encode_bytes(bytes: &[u8], flavour: &Flavour) -> Vec<u8> {
-    let target_length = encode_calc_byte_size(bytes);
-    let mut res: Vec<u8> = vec![0; target_length];
-    let length = bytes.len();
-    let mut position = 0;
-    for i in 1..length {
-        if i % 3 == 2 {
-            let mut trio = [0; 3];
-            trio[..3].clone_from_slice(&bytes[i - 2..i + 1]);
-            let quartet = encode_trio(&trio, flavour);
-            res[position..position + 4].clone_from_slice(&quartet);
-            position += 4;
-        }
+    flavour.engine().encode(bytes)
+}
+
+/// Encodes with the given alphabet and padding without building a [`Base64Engine`] first.
+pub fn base64_encode_bytes_alphabet(bytes: &[u8], alphabet: &[u8; 64], pad: Option<u8>) -> Vec<u8> {
+    encode_bytes(bytes, &Base64Engine::encoding_only(alphabet, pad))
+}
+
+/// Encodes with the default flavour while feeding the input to `hasher`, e.g. for a checksum, in a
+/// single pass over the bytes.
+pub fn base64_encode_and_checksum<H: Hasher>(bytes: &[u8], hasher: &mut H) -> Vec<u8> {
+    const CHUNK: usize = 3 * 1024;
+    let engine = Flavour::default().engine();
+    let mut res = vec![0; encode_calc_byte_size(bytes, engine.pad_char().is_some())];
+    // whole chunks are multiples of 3, so each encodes to its own slice of the output
+    for (chunk, encoded) in bytes.chunks(CHUNK).zip(res.chunks_mut(encoded_len(CHUNK))) {
+        hasher.write(chunk);
+        encode_into(chunk, engine, encoded);
+    }
+    res
+}
+
+/// Encodes with the default flavour and pads the result with `=` up to `width` characters, for
+/// fixed width record formats. Fails if the encoding alone is longer than `width`.
+pub fn base64_encode_bytes_fixed_width(bytes: &[u8], width: usize) -> Result<Vec<u8>, Base64Error> {
+    let mut encoded = base64_encode_bytes(bytes, &Flavour::default());
+    if encoded.len() > width {
+        return Err(Base64Error::new(&format!("Encoded length {} exceeds the width of {}", encoded.len(), width)));
     }
+    encoded.resize(width, PADDING_CHAR);
+    Ok(encoded)
+}
+
+/// Decodes a field written by [`base64_encode_bytes_fixed_width`], ignoring the padding beyond
+/// the final group.
+pub fn base64_decode_bytes_fixed_width(input: &[u8]) -> Result<Vec<u8>, Base64Error> {
+    let data_length = input.iter().rposition(|&b| b != PADDING_CHAR).map_or(0, |i| i + 1);
+    let group_end = data_length.div_ceil(4) * 4;
+    base64_decode_bytes(&input[..group_end.min(input.len())], &Flavour::default())
+}
+
+pub(crate) fn encode_bytes(bytes: &[u8], engine: &Base64Engine) -> Vec<u8> {
+    let mut res: Vec<u8> = vec![0; encode_calc_byte_size(bytes, engine.pad_char().is_some())];
+    encode_into(bytes, engine, &mut res);
+    res
+}
+
+/// Encodes into `res`, which must be exactly as long as `encode_calc_byte_size` for the engine.
+pub(crate) fn encode_into(bytes: &[u8], engine: &Base64Engine, res: &mut [u8]) {
+    let target_length = res.len();
+    let length = bytes.len();
     let remaining = length % 3;
+    for (trio, quartet) in bytes[..length - remaining].chunks_exact(3).zip(res.chunks_exact_mut(4)) {
+        quartet.copy_from_slice(&encode_trio(trio, engine));
+    }
+    // aligned input, the common case, has no final group to complete
     if remaining > 0 {
         let mut remaining_bytes = vec![0; remaining];
         remaining_bytes[0..remaining].clone_from_slice(&bytes[length - remaining..length]);
-        let quartet = if remaining == 2 { encode_duo(&remaining_bytes, flavour) }
-            else { encode_uno(&remaining_bytes, flavour) };
-        res[target_length - quartet.len()..target_length].clone_from_slice(&quartet);
+        let quartet = if remaining == 2 { encode_duo(&remaining_bytes, engine) }
+            else { encode_uno(&remaining_bytes, engine) };
+        let chars = if engine.pad_char().is_some() { quartet.len() } else { remaining + 1 };
+        res[target_length - chars..target_length].clone_from_slice(&quartet[..chars]);
     }
-    res.clone()
 }
 
-pub fn base64_decode_bytes_standard(bytes: &[u8]) -> Result<Vec<u8>, PaddingError> {
+/// Encodes with the default flavour between a prefix and a suffix, e.g. `<<<` and `>>>`, all in
+/// a single allocation.
+pub fn base64_encode_framed(bytes: &[u8], prefix: &str, suffix: &str) -> String {
+    let encoded_length = encoded_len(bytes.len());
+    let mut res = Vec::with_capacity(prefix.len() + encoded_length + suffix.len());
+    res.extend_from_slice(prefix.as_bytes());
+    res.resize(prefix.len() + encoded_length, 0);
+    encode_into(bytes, Flavour::default().engine(), &mut res[prefix.len()..]);
+    res.extend_from_slice(suffix.as_bytes());
+    String::from_utf8(res).expect("base64 between UTF-8 affixes is valid UTF-8")
+}
+
+/// Checks and removes the prefix and suffix written by [`base64_encode_framed`], then decodes the
+/// middle with the default flavour.
+pub fn base64_decode_framed(input: &str, prefix: &str, suffix: &str) -> Result<Vec<u8>, Base64Error> {
+    let payload = input.strip_prefix(prefix)
+        .ok_or_else(|| Base64Error::malformed(DecodeError::InvalidFormat, &format!("Missing prefix \"{}\"", prefix)))?
+        .strip_suffix(suffix)
+        .ok_or_else(|| Base64Error::malformed(DecodeError::InvalidFormat, &format!("Missing suffix \"{}\"", suffix)))?;
+    base64_decode_bytes(payload.as_bytes(), &Flavour::default())
+}
+
+pub fn base64_decode_bytes_standard(bytes: &[u8]) -> Result<Vec<u8>, Base64Error> {
     base64_decode_bytes(bytes, &Base64Standard)
 }
 
-pub fn base64_decode_bytes_url(bytes: &[u8]) -> Result<Vec<u8>, PaddingError> {
+pub fn base64_decode_bytes_url(bytes: &[u8]) -> Result<Vec<u8>, Base64Error> {
     base64_decode_bytes(bytes, &Base64Url)
 }
 
-pub fn base64_decode_bytes(bytes: &[u8], flavour: &Flavour) -> Result<Vec<u8>, PaddingError> {
-    let target_length = decode_calc_byte_size(bytes);
-    let mut res = vec![0; target_length];
-    let source_length = bytes.len();
+/// Decodes with the strict engine of the flavour, so characters outside of its alphabet are rejected.
+/// Use [`Base64Engine::permissive`] to skip them instead. A leading UTF-8 BOM, left over from
+/// reading a text file, is skipped. The error's [`Base64Error::decode_error`] tells which kind of
+/// problem the input has and is always set; it is not returned on its own so that the message
+/// keeps the details, e.g. where the padding went wrong.
+pub fn base64_decode_bytes(bytes: &[u8], flavour: &Flavour) -> Result<Vec<u8>, Base64Error> {
+    flavour.engine().decode(strip_bom(bytes))
+}
+
+/// Skips a leading UTF-8 BOM, left over from reading a text file.
+pub(crate) fn strip_bom(input: &[u8]) -> &[u8] {
+    input.strip_prefix(UTF8_BOM).unwrap_or(input)
+}
+
+/// Decodes with the default flavour into a `Cow`. The decoded bytes are always owned for now;
+/// the signature leaves room to borrow from `input` when a zero-copy path exists.
+pub fn base64_decode_cow(input: &[u8]) -> Result<Cow<'_, [u8]>, Base64Error> {
+    base64_decode_bytes(input, &Flavour::default()).map(Cow::Owned)
+}
+
+/// Decodes with the default flavour, accepting missing padding like [`base64_decode_relaxed`], and
+/// tells whether the input was canonical: fully padded, with the unused trailing bits zero, so that
+/// encoding the bytes again gives the input back. `TR==` decodes like `TQ==` but is not canonical.
+pub fn base64_decode_bytes_canonical(input: &[u8]) -> Result<(Vec<u8>, bool), Base64Error> {
+    let decoded = base64_decode_relaxed(input)?;
+    let canonical = Flavour::default().engine().encode(&decoded) == input;
+    Ok((decoded, canonical))
+}
+
+/// Decodes with the given alphabet and padding, the counterpart of [`base64_encode_bytes_alphabet`].
+/// Fails if the alphabet repeats a character, is not printable ASCII or contains the padding.
+pub fn base64_decode_bytes_alphabet(bytes: &[u8], alphabet: &[u8; 64], pad: Option<u8>) -> Result<Vec<u8>, Base64Error> {
+    let engine = Base64Engine::standard().with_alphabet(alphabet)?;
+    let engine = match pad {
+        Some(pad) => engine.with_pad(pad)?,
+        None => engine.no_pad()
+    };
+    engine.build()?.decode(bytes)
+}
+
+/// Decodes strings where a partial find and replace left both alphabets mixed: `+` and `-` are
+/// both accepted for the 62nd character, `/` and `_` both for the 63rd.
+pub fn base64_decode_mixed(input: &[u8]) -> Result<Vec<u8>, Base64Error> {
+    MIXED.decode(input)
+}
+
+/// Decodes base64 in either the standard or the URL safe alphabet, for input collected from many
+/// sources. The same as [`base64_decode_mixed`], which also covers input in a single alphabet.
+#[deprecated(note = "use base64_decode_mixed, which accepts both alphabets")]
+pub fn base64_decode_universal(input: &[u8]) -> Result<Vec<u8>, Base64Error> {
+    base64_decode_mixed(input)
+}
+
+/// Guesses the flavour of base64 from an unknown source by its 62nd and 63rd characters: `-` or `_`
+/// mean [`Flavour::Base64Url`], `+` or `/` [`Flavour::Base64Standard`]. Returns `None` when the
+/// input has neither, which both flavours decode alike, or both, which neither decodes.
+pub fn detect_flavour(input: &[u8]) -> Option<Flavour> {
+    let url_safe = input.iter().any(|b| *b == b'-' || *b == b'_');
+    let standard = input.iter().any(|b| *b == b'+' || *b == b'/');
+    match (standard, url_safe) {
+        (true, false) => Some(Base64Standard),
+        (false, true) => Some(Base64Url),
+        _ => None
+    }
+}
+
+/// Tells whether two encodings decode to the same bytes, whatever their alphabet (standard or URL
+/// safe) and whether they are padded, e.g. for deduplication.
+pub fn decoded_equal(a: &str, b: &str) -> Result<bool, Base64Error> {
+    fn decode(s: &str) -> Result<Vec<u8>, Base64Error> {
+        MIXED_NO_PAD.decode(s.trim_end_matches('=').as_bytes())
+    }
+    Ok(decode(a)? == decode(b)?)
+}
+
+/// Decodes base64 of the default flavour unless it would produce more than `max_output` bytes, which is checked
+/// before anything is allocated. Use it for tokens of known maximum size from untrusted sources.
+pub fn base64_decode_max(input: &[u8], max_output: usize) -> Result<Vec<u8>, Base64Error> {
+    let decoded_length = decode_calc_byte_size(input, Flavour::default().engine());
+    if decoded_length > max_output {
+        return Err(Base64Error::malformed(DecodeError::LimitExceeded, &format!(
+            "Decoded length {} exceeds the maximum of {}", decoded_length, max_output)));
+    }
+    base64_decode_bytes(input, &Flavour::default())
+}
+
+/// Decodes base64 of the default flavour unless it holds more than `max_quartets` groups of 4
+/// characters, the final short group included, checked before any work. Bounds the time a request
+/// handler spends on attacker controlled input.
+pub fn base64_decode_budgeted(input: &[u8], max_quartets: usize) -> Result<Vec<u8>, Base64Error> {
+    let quartets = input.len().div_ceil(4);
+    if quartets > max_quartets {
+        return Err(Base64Error::malformed(DecodeError::LimitExceeded, &format!(
+            "Input of {} quartets exceeds the budget of {}", quartets, max_quartets)));
+    }
+    base64_decode_bytes(input, &Flavour::default())
+}
+
+/// Decodes base64 of the default flavour and appends zero bytes up to the next multiple of
+/// `block`, e.g. 16 for a block cipher. Output which already fills whole blocks is left as is.
+pub fn base64_decode_padded_to_block(input: &[u8], block: usize) -> Result<Vec<u8>, Base64Error> {
+    if block == 0 {
+        return Err(Base64Error::new("Block size must be greater than zero"));
+    }
+    let mut decoded = base64_decode_bytes(input, &Flavour::default())?;
+    decoded.resize(decoded.len().next_multiple_of(block), 0);
+    Ok(decoded)
+}
+
+/// Decodes only the quartets needed for the first `n_bytes` bytes, e.g. to peek at the header of
+/// a large payload; the rest of the input is neither decoded nor validated. Returns fewer bytes if
+/// the input decodes to fewer.
+pub fn base64_decode_prefix(input: &[u8], n_bytes: usize) -> Result<Vec<u8>, Base64Error> {
+    let needed = n_bytes.div_ceil(3).saturating_mul(4);
+    let mut decoded = Flavour::default().engine().decode(&input[..needed.min(input.len())])?;
+    decoded.truncate(n_bytes);
+    Ok(decoded)
+}
+
+/// Decodes the complete quartets available in a growing buffer, e.g. one filled from a socket.
+/// Returns the decoded bytes and the number of input bytes consumed, always a multiple of 4; the
+/// incomplete trailing group is left for the next call.
+pub fn base64_decode_partial(input: &[u8]) -> Result<(Vec<u8>, usize), Base64Error> {
+    let consumed = input.len() / 4 * 4;
+    let decoded = base64_decode_bytes(&input[..consumed], &Flavour::default())?;
+    Ok((decoded, consumed))
+}
+
+/// Decodes wrapped base64 of the default flavour given line by line, e.g. from `BufRead::lines`,
+/// without joining the lines first. Surrounding whitespace of each line is ignored, and quartets
+/// may span lines. Error positions count the significant characters only.
+pub fn base64_decode_lines<I: IntoIterator<Item = S>, S: AsRef<str>>(lines: I) -> Result<Vec<u8>, Base64Error> {
+    let mut decoder = StreamDecoder::new(Flavour::default().engine());
+    let mut decoded = Vec::new();
+    for line in lines {
+        decoder.push(line.as_ref().trim().as_bytes(), &mut decoded)?;
+    }
+    decoder.finish(&mut decoded)?;
+    Ok(decoded)
+}
+
+/// Decodes base64 of the default flavour into the front of its own buffer, which is then
+/// truncated to the decoded length. The buffer is left untouched when decoding fails.
+pub fn base64_decode_in_place(buf: &mut Vec<u8>) -> Result<(), Base64Error> {
+    let engine = Flavour::default().engine();
+    let decoded_length = decoded_len_checked(buf, engine)?;
+    let table = engine.decode_table();
+    let quartets = buf.len().div_ceil(4);
+    for q in 0..quartets {
+        // quartet q is read before the 3 bytes written at 3 * q could reach it
+        let mut converted = [PADDING_CODE; 4];
+        for (i, b) in buf[q * 4..buf.len().min(q * 4 + 4)].iter().enumerate() {
+            converted[i] = table[*b as usize];
+        }
+        let (decoded, size) = decode_checked_quartet(converted, q == quartets - 1)
+            .map_err(|i| invalid_character(buf[q * 4 + i], q * 4 + i))?;
+        buf[q * 3..q * 3 + size].copy_from_slice(&decoded[..size]);
+    }
+    buf.truncate(decoded_length);
+    Ok(())
+}
+
+/// Validates base64 of the default flavour and returns the number of bytes it decodes to, without
+/// producing them. Misplaced padding and invalid characters are reported with their position.
+pub fn base64_decoded_len_checked(input: &[u8]) -> Result<usize, Base64Error> {
+    decoded_len_checked(input, Flavour::default().engine())
+}
+
+fn decoded_len_checked(input: &[u8], engine: &Base64Engine) -> Result<usize, Base64Error> {
+    if input.is_empty() {
+        return Ok(0);
+    }
+    check_padding(input, engine)?;
+    let table = engine.decode_table();
+    let quartets = input.len().div_ceil(4);
+    for (q, chunk) in input.chunks(4).enumerate() {
+        let mut converted = [PADDING_CODE; 4];
+        for (i, b) in chunk.iter().enumerate() {
+            converted[i] = table[*b as usize];
+        }
+        decode_checked_quartet(converted, q == quartets - 1).map_err(|i| invalid_character(chunk[i], q * 4 + i))?;
+    }
+    Ok(decode_calc_byte_size(input, engine))
+}
+
+/// Decodes base64 of the default flavour which may have lost a single trailing `=`. Input one
+/// character short of a padded group gets its pad back; anything shorter is left alone and fails
+/// as usual. The flag tells whether the pad was restored.
+pub fn base64_decode_repair(input: &[u8]) -> Result<(Vec<u8>, bool), Base64Error> {
+    if input.len() % 4 != 3 {
+        return Ok((base64_decode_bytes(input, &Flavour::default())?, false));
+    }
+    let mut repaired = Vec::with_capacity(input.len() + 1);
+    repaired.extend_from_slice(input);
+    repaired.push(PADDING_CHAR);
+    Ok((base64_decode_bytes(&repaired, &Flavour::default())?, true))
+}
+
+/// Decodes base64 of the default flavour whatever is left of its padding: `TQ`, `TQ=` and `TQ==`
+/// all give `M`. Unlike [`base64url_decode_nopad`], complete padding is accepted too. The missing
+/// pads are restored before decoding, so a final group of a single character still fails.
+pub fn base64_decode_relaxed(input: &[u8]) -> Result<Vec<u8>, Base64Error> {
+    let engine = Flavour::default().engine();
+    let data_length = input.iter().rposition(|&b| b != PADDING_CHAR).map_or(0, |last| last + 1);
+    let completed_length = data_length + (4 - data_length % 4) % 4;
+    if data_length % 4 == 1 || input.len() >= completed_length {
+        // a single final character cannot be padded; otherwise the padding is already complete,
+        // or too long, which decoding reports
+        return engine.decode(input);
+    }
+    let mut completed = Vec::with_capacity(completed_length);
+    completed.extend_from_slice(input);
+    completed.resize(completed_length, PADDING_CHAR);
+    engine.decode(&completed)
+}
+
+/// Translates the whole input to sextets in a single pass, which is also where characters outside
+/// of the alphabet are found, then packs the quartets into the front of the same buffer, which
+/// becomes the output.
+pub(crate) fn decode_bytes(bytes: &[u8], engine: &Base64Engine) -> Result<Vec<u8>, Base64Error> {
     const CHUNK: usize = 4;
-    let modulo_max = CHUNK - 1;
-    let mut position = 0;
-    for i in 1..source_length - CHUNK {
-        if i % CHUNK == modulo_max {
-            let converted = convert_encoded_bytes(&bytes[i - modulo_max..i + 1], flavour);
-            let decoded = decode_quartet(&converted);
-            res[position..position + 3].clone_from_slice(&decoded);
-            position += 3;
+    let source_length = bytes.len();
+    if source_length == 0 {
+        return Ok(vec![]);
+    }
+    let mut sextets = convert_encoded_bytes(bytes, engine)?;
+    if sextets.iter().all(|&s| s == PADDING_CODE) {
+        return Err(Base64Error::malformed(DecodeError::InvalidPadding, "Input consists only of padding"));
+    }
+    check_padding(bytes, engine)?;
+    // without padding the final group may be short
+    let tail = match source_length % CHUNK { 0 => CHUNK, remaining => remaining };
+    let mut last = [PADDING_CODE; CHUNK];
+    last[..tail].copy_from_slice(&sextets[source_length - tail..]);
+    let quartets = (source_length - tail) / CHUNK;
+    decode_quartets(&mut sextets, quartets);
+    let decoded = decode_incomplete(&last)
+        .map_err(|error| Base64Error::malformed(DecodeError::InvalidPadding, &error.to_string()))?;
+    let length = quartets * 3 + decoded.len();
+    sextets[quartets * 3..length].copy_from_slice(&decoded);
+    sextets.truncate(length);
+    Ok(sextets)
+}
+
+/// Packs the first `quartets` quartets of `buffer` into bytes, written over its front.
+fn decode_quartets(buffer: &mut [u8], quartets: usize) {
+    for q in 0..quartets {
+        // quartet q is read before the 3 bytes written at 3 * q could reach it
+        let decoded = decode_quartet(&buffer[q * 4..q * 4 + 4]);
+        buffer[q * 3..q * 3 + 3].copy_from_slice(&decoded);
+    }
+}
+
+/// Decodes into `out` without allocating. Characters are not skipped, so this is always strict.
+/// Returns the number of bytes written.
+pub(crate) fn decode_into(bytes: &[u8], engine: &Base64Engine, out: &mut [u8]) -> Result<usize, Base64Error> {
+    if bytes.is_empty() {
+        return Ok(0);
+    }
+    check_padding(bytes, engine)?;
+    let target_length = decode_calc_byte_size(bytes, engine);
+    if out.len() < target_length {
+        return Err(Base64Error::new(&format!("Output buffer of {} bytes too small, {} needed", out.len(), target_length)));
+    }
+    let table = engine.decode_table();
+    let quartets = bytes.len().div_ceil(4);
+    for (q, chunk) in bytes.chunks(4).enumerate() {
+        // a short unpadded final group is completed with padding
+        let mut converted = [PADDING_CODE; 4];
+        for (i, b) in chunk.iter().enumerate() {
+            converted[i] = table[*b as usize];
+        }
+        let (decoded, size) = decode_checked_quartet(converted, q == quartets - 1)
+            .map_err(|i| invalid_character(chunk[i], q * 4 + i))?;
+        out[q * 3..q * 3 + size].copy_from_slice(&decoded[..size]);
+    }
+    Ok(target_length)
+}
+
+/// Checks that padding, if any, only ends the input and has exactly the length which completes
+/// the final quartet, e.g. one `=` after 3 data characters, then checks the final group.
+pub(crate) fn check_padding(bytes: &[u8], engine: &Base64Engine) -> Result<(), Base64Error> {
+    let pads = bytes.iter().filter(|&&b| engine.is_pad(b)).count();
+    if pads > 0 {
+        let data_length = bytes.len() - pads;
+        let expected = (4 - data_length % 4) % 4;
+        if pads != expected {
+            return Err(Base64Error::malformed(DecodeError::InvalidPadding, &format!(
+                "Expected {} padding characters after {} data characters, found {}", expected, data_length, pads)));
+        }
+        if let Some(position) = bytes[..data_length].iter().position(|&b| engine.is_pad(b)) {
+            let group_end = position + bytes[position..].iter().take_while(|&&b| engine.is_pad(b)).count();
+            if group_end.is_multiple_of(4) {
+                // a well formed padded group which is not the last one
+                return Err(data_after_padding(group_end - 1));
+            }
+            return Err(invalid_character(bytes[position], position));
+        }
+        // three pads complete a group of a single character, which is still a cut stream
+        return check_final_group(data_length, false);
+    }
+    check_final_group(bytes.len(), engine.pad_char().is_some())
+}
+
+/// Checks that the input ends with a complete quartet. A final group of 2 or 3 characters is
+/// a valid group which lost its padding (accepted when unpadded), while a single character can
+/// only come from a cut stream.
+pub(crate) fn check_final_group(length: usize, padded: bool) -> Result<(), Base64Error> {
+    match length % 4 {
+        0 => Ok(()),
+        1 => Err(Base64Error::malformed(DecodeError::InvalidLength, &format!(
+            "Truncated input: final group at position {} has a single character", length - 1))),
+        _ if !padded => Ok(()),
+        remaining => Err(Base64Error::malformed(DecodeError::InvalidPadding, &format!(
+            "Missing padding: final group at position {} has {} characters", length - remaining, remaining)))
+    }
+}
+
+/// Decodes a quartet translated through the decode table, checking every character. Padding is
+/// only accepted in the `last` quartet. Returns the bytes with their count, or the index of the
+/// offending character.
+pub(crate) fn decode_checked_quartet(mut converted: [u8; 4], last: bool) -> Result<([u8; 3], usize), usize> {
+    let data_chars = if last { converted.iter().position(|&c| c == PADDING_CODE).unwrap_or(4) } else { 4 };
+    if data_chars < 2 {
+        return Err(data_chars);
+    }
+    for (i, c) in converted.iter().enumerate() {
+        let misplaced_pad = *c == PADDING_CODE && i < data_chars;
+        let data_after_pad = *c != PADDING_CODE && i >= data_chars;
+        if *c == INVALID_CHAR || misplaced_pad || data_after_pad {
+            return Err(i);
         }
     }
-    let converted = convert_encoded_bytes(&bytes[(source_length - CHUNK)..source_length], flavour);
-    let decoded = decode_incomplete(&converted)?;
-    res[target_length - decoded.len()..target_length].clone_from_slice(&decoded[0..decoded.len()]);
-    Ok(res)
+    converted[data_chars..].fill(0);
+    Ok((decode_quartet(&converted), data_chars - 1))
+}
+
+pub(crate) fn invalid_character(b: u8, position: usize) -> Base64Error {
+    DecodeError::InvalidByte { position, byte: b }.into()
+}
+
+/// The error of a padded group, whose last character is at `position`, which is not the last one.
+pub(crate) fn data_after_padding(position: usize) -> Base64Error {
+    Base64Error::malformed(DecodeError::InvalidPadding,
+                           &format!("Padded group ending at position {} is followed by more data", position))
+}
+
+/// The error of a stream which ends inside the group of `count` characters at `position`: a single
+/// character can only come from a cut stream, while 2 or 3 lack their padding.
+pub(crate) fn truncated(position: usize, count: usize) -> Base64Error {
+    let kind = if count == 1 { DecodeError::InvalidLength } else { DecodeError::InvalidPadding };
+    Base64Error::malformed(kind, &format!("Truncated input: final group at position {} has {} characters", position, count))
+}
+
+/// The padding character of the padded flavours, `=`.
+pub const fn default_pad_char() -> u8 {
+    PADDING_CHAR
+}
+
+/// The padding character `engine` encodes with, or `None` if it does not pad. Same as
+/// [`Base64Engine::pad_char`], for tooling which works with free functions.
+pub fn active_pad_char(engine: &Base64Engine) -> Option<u8> {
+    engine.pad_char()
+}
+
+/// Number of characters of the padded encoding of `n` bytes. Overflows for `n` above
+/// `usize::MAX / 4 * 3`, which no slice reaches; use [`checked_encoded_len`] for sizes taken
+/// from elsewhere, e.g. file metadata.
+pub const fn encoded_len(n: usize) -> usize {
+    n.div_ceil(3) * 4
+}
+
+/// Number of characters of the padded encoding of `n` bytes, or `None` if it does not fit in a
+/// `usize`.
+pub const fn checked_encoded_len(n: usize) -> Option<usize> {
+    n.div_ceil(3).checked_mul(4)
+}
+
+/// Number of bytes decoded from `n` significant characters (padding excluded), or `None` if no
+/// valid encoding has that many, i.e. a single character remains after the last quartet. The
+/// result is always smaller than `n`, so it cannot overflow.
+pub const fn checked_decoded_len(n: usize) -> Option<usize> {
+    match n % 4 {
+        1 => None,
+        remaining => Some(n / 4 * 3 + remaining * 3 / 4)
+    }
+}
+
+/// Ratio of the encoded size to `input_len` bytes, without encoding anything: padding counts when
+/// `with_padding`, and `wrap`, given as `(line_length, separator_length)` like `(76, 2)` for MIME,
+/// adds the separators between lines. An empty input has a ratio of 1.
+pub fn encoding_overhead(input_len: usize, with_padding: bool, wrap: Option<(usize, usize)>) -> f64 {
+    if input_len == 0 {
+        return 1.0;
+    }
+    let chars = encoded_len_with_padding(input_len, with_padding);
+    let separators = match wrap {
+        Some((line_length, separator_length)) if line_length > 0 => (chars.div_ceil(line_length) - 1) * separator_length,
+        _ => 0
+    };
+    (chars + separators) as f64 / input_len as f64
+}
+
+/// Number of characters of `input` which carry data: padding is never counted, whitespace such as
+/// line breaks only when `ignore_whitespace` is false. A valid input of `n` significant characters
+/// decodes to `n * 3 / 4` bytes.
+pub fn significant_char_count(input: &[u8], ignore_whitespace: bool) -> usize {
+    input.iter().filter(|b| **b != PADDING_CHAR && !(ignore_whitespace && b.is_ascii_whitespace())).count()
+}
+
+pub(crate) fn encode_calc_byte_size(bytes: &[u8], with_padding: bool) -> usize {
+    // a slice has at most isize::MAX bytes, whose encoding (2/3 of usize::MAX) still fits
+    encoded_len_with_padding(bytes.len(), with_padding)
 }
 
-fn encode_calc_byte_size(bytes: &[u8]) -> usize {
-    let res = ((bytes.len() as f32 * 4. / 3.) / 4.).ceil() * 4.;
-    return res as usize;
+fn encoded_len_with_padding(n: usize, with_padding: bool) -> usize {
+    if with_padding {
+        encoded_len(n)
+    } else {
+        n / 3 * 4 + [0, 2, 3][n % 3]
+    }
 }
 
-fn encode_trio(bytes: &[u8], flavour: &Flavour) -> [u8; 4] {
+fn encode_trio(bytes: &[u8], engine: &Base64Engine) -> [u8; 4] {
     assert_eq!(bytes.len(), 3);
     let quartet = bytes_encode_trio(bytes);
-    let table = choose_encoding_table(flavour);
-    return [
+    let table = engine.alphabet();
+    [
         table[quartet[0]],
         table[quartet[1]],
         table[quartet[2]],
         table[quartet[3]]
-    ];
+    ]
 }
 
-fn encode_duo(bytes: &[u8], flavour: &Flavour) -> [u8; 4] {
+fn encode_duo(bytes: &[u8], engine: &Base64Engine) -> [u8; 4] {
     assert_eq!(bytes.len(), 2);
     let trio = [bytes[0], bytes[1], 63];
     let quartet = bytes_encode_trio(&trio);
-    let table = choose_encoding_table(flavour);
-    return [
+    let table = engine.alphabet();
+    [
         table[quartet[0]],
         table[quartet[1]],
         table[quartet[2]],
-        PADDING_CHAR
-    ];
+        engine.pad_char().unwrap_or(PADDING_CHAR) // cut off by the caller when unpadded
+    ]
 }
 
-fn encode_uno(bytes: &[u8], flavour: &Flavour) -> [u8; 4] {
+fn encode_uno(bytes: &[u8], engine: &Base64Engine) -> [u8; 4] {
     assert_eq!(bytes.len(), 1);
     let trio = [bytes[0], 15, 255];
     let quartet = bytes_encode_trio(&trio);
-    let table = choose_encoding_table(flavour);
-    return [
+    let table = engine.alphabet();
+    [
         table[quartet[0]],
         table[quartet[1]],
-        PADDING_CHAR,
-        PADDING_CHAR
-    ];
-}
-
-fn choose_encoding_table(flavour: &Flavour) -> &'static [u8] {
-    let table = if *flavour == Base64Standard { BASE_64_ENCODING_CHARS } else { BASE_64_ENCODING_CHARS_URL };
-    table
+        engine.pad_char().unwrap_or(PADDING_CHAR),
+        engine.pad_char().unwrap_or(PADDING_CHAR)
+    ]
 }
 
-fn bytes_encode_trio(bytes: &[u8]) -> [usize; 4] {
+pub(crate) const fn bytes_encode_trio(bytes: &[u8]) -> [usize; 4] {
     let i = bytes[0];
     let first = i >> 2;
     let temp = (i & 3) << 4;
@@ -227,22 +997,34 @@ fn bytes_encode_trio(bytes: &[u8]) -> [usize; 4] {
     let k = bytes[2];
     let third = k >> 6 | temp1;
     let fourth = k & 63;
-    return [first as usize, second as usize, third as usize, fourth as usize];
+    [first as usize, second as usize, third as usize, fourth as usize]
 }
 
-fn decode_calc_byte_size(bytes: &[u8]) -> usize {
-    let real_length = bytes.iter().position(|&r| r == '=' as u8).unwrap_or(bytes.len());
-    (real_length as f32 * 3. / 4.).floor() as usize
+pub(crate) fn decode_calc_byte_size(bytes: &[u8], engine: &Base64Engine) -> usize {
+    // only padding in the final quartet counts, so the size always covers the complete quartets
+    let padding = bytes.iter().rev().take(4).take_while(|&&r| engine.is_pad(r)).count();
+    let real_length = bytes.len() - padding;
+    real_length / 4 * 3 + real_length % 4 * 3 / 4
 }
 
-fn convert_encoded_bytes(bytes: &[u8], flavour: &Flavour) -> Vec<u8> {
-    let table = if *flavour == Base64Standard { CHARS_BASE_64_ENCODING.to_vec() } else { CHARS_BASE_64_ENCODING_URL.to_vec() };
-    bytes.iter().map(|x| table[*x as usize]).collect()
+/// Translates the whole input to sextets (padding to `PADDING_CODE`) in a single pass, which is
+/// also where characters outside of the alphabet are found.
+fn convert_encoded_bytes(bytes: &[u8], engine: &Base64Engine) -> Result<Vec<u8>, Base64Error> {
+    let table = engine.decode_table();
+    let mut sextets = Vec::with_capacity(bytes.len());
+    for (position, b) in bytes.iter().enumerate() {
+        let sextet = table[*b as usize];
+        if sextet == INVALID_CHAR {
+            return Err(invalid_character(*b, position));
+        }
+        sextets.push(sextet);
+    }
+    Ok(sextets)
 }
 
 fn decode_incomplete(bytes: &[u8]) -> Result<Vec<u8>, PaddingError> {
     let mut quartet: [u8; 4] = [0; 4];
-    let pad_code = CHARS_BASE_64_ENCODING['=' as usize];
+    let pad_code = PADDING_CODE;
     let pad_pos = bytes.iter().position(|&r| r == pad_code).unwrap_or(bytes.len());
     quartet[0..pad_pos].clone_from_slice(&bytes[0..pad_pos]);
     let temp = decode_quartet(&quartet);
@@ -260,28 +1042,30 @@ fn decode_quartet(bytes: &[u8]) -> [u8; 3] {
     let k = bytes[2];
     let l = bytes[3];
     let first = (i << 2) | (j >> 4);
-    let second = (j << 4) | (k >> 2) & 0xff;
-    let third = ((k << 6) | l) & 0xff;
-    return [first as u8, second as u8, third as u8];
+    let second = (j << 4) | (k >> 2);
+    let third = (k << 6) | l;
+    [first, second, third]
 }
 
 
 #[cfg(test)]
 mod tests {
+    use std::io::ErrorKind;
+
     use super::*;
 
     #[test]
     fn when_encode_should_produce_right_results() {
         let bytes = "Man".as_bytes();
-        let quartet = encode_trio(bytes, &Base64Standard);
+        let quartet = encode_trio(bytes, Base64Standard.engine());
         convert_to_str_check(&quartet, "TWFu");
 
         let duo = "Ma".as_bytes();
-        let quartet_duo = encode_duo(duo, &Base64Standard);
+        let quartet_duo = encode_duo(duo, Base64Standard.engine());
         convert_to_str_check(&quartet_duo, "TWE=");
 
         let uno = "M".as_bytes();
-        let quartet_uno = encode_uno(uno, &Base64Standard);
+        let quartet_uno = encode_uno(uno, Base64Standard.engine());
         convert_to_str_check(&quartet_uno, "TQ==");
     }
 
@@ -294,20 +1078,21 @@ mod tests {
     #[test]
     fn when_encode_calc_byte_size_should_produce_right_size() {
         let bytes = "Man".as_bytes();
-        let res = encode_calc_byte_size(bytes);
+        let res = encode_calc_byte_size(bytes, true);
         assert_eq!(4, res);
         let bytes2 = "Man1".as_bytes();
-        let res = encode_calc_byte_size(bytes2);
+        let res = encode_calc_byte_size(bytes2, true);
         assert_eq!(8, res);
         let bytes3 = "Man12".as_bytes();
-        let res = encode_calc_byte_size(bytes3);
+        let res = encode_calc_byte_size(bytes3, true);
         assert_eq!(8, res);
         let bytes3 = "Man1227".as_bytes();
-        let res = encode_calc_byte_size(bytes3);
+        let res = encode_calc_byte_size(bytes3, true);
         assert_eq!(12, res);
     }
 
     #[test]
+    #[allow(clippy::useless_vec)]
     fn when_base64_encode_bytes_should_produce_right_vector() {
         let input: Vec<&[u8]> = vec!["Man", "Assuming", "Olá! isto é um teste", "你好，这是一个测试"]
             .iter().map(|x| x.as_bytes()).collect();
@@ -338,9 +1123,10 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::char_lit_as_u8)]
     fn when_decode_trio_should_decode() {
         let raw_input: [u8; 4] = ['T' as u8, 'W' as u8, 'E' as u8, '=' as u8];
-        let converted = convert_encoded_bytes(&raw_input, &Base64Standard);
+        let converted = convert_encoded_bytes(&raw_input, Base64Standard.engine()).unwrap();
         let bytes = converted.as_slice();
         assert_eq!(19, bytes[0]);
         assert_eq!(22, bytes[1]);
@@ -356,7 +1142,7 @@ mod tests {
     #[test]
     fn when_decode_calc_byte_size_should_give_right_size() {
         fn perform_test(expected: usize, str: &str) {
-            assert_eq!(expected, decode_calc_byte_size(str.as_bytes()));
+            assert_eq!(expected, decode_calc_byte_size(str.as_bytes(), Base64Standard.engine()));
         }
 
         perform_test(1, "TQ==");
@@ -396,6 +1182,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::useless_vec)]
     fn when_base64_encode_should_base64_decode() {
         for s in vec!["This is a nice text.", "Este é um texto super interessante!",
                       "एक बहुत अच्छी रात और एक अच्छा कल", "一个非常美好的夜晚和明天美好的一天"] {
@@ -415,13 +1202,720 @@ mod tests {
 
     #[test]
     fn when_base64_encode_to_file_should_create_file() {
-        let sample_image = PathBuf::from("resources/sample_image.png");
-        let target_image = PathBuf::from("sample_image_base64.txt");
-        let res = base64_encode_to_file(sample_image, target_image, &Base64Standard);
+        let res = base64_encode_to_file("resources/sample_image.png", "sample_image_base64.txt", &Base64Standard);
         assert!(res.is_ok());
-        let target_image_final = PathBuf::from("sample_image_base64.png");
-        let res = base64_decode_from_file(PathBuf::from("sample_image_base64.txt"),
-                                target_image_final, &Base64Standard);
+        let res = base64_decode_from_file("sample_image_base64.txt", "sample_image_base64.png", &Base64Standard);
         assert!(res.is_ok());
+        assert_eq!(fs::read("resources/sample_image.png").unwrap(), fs::read("sample_image_base64.png").unwrap());
+        let encoded = base64_encode_file(Path::new("resources/sample_image.png"), &Base64Url);
+        assert_eq!(encoded.unwrap(), base64_encode_file_str_url("resources/sample_image.png").unwrap());
     }
-}
+
+    #[test]
+    fn when_base64url_decode_percent_should_unescape_first() {
+        let res = base64url_decode_percent("%2D%5f8%3D");
+        assert_eq!(vec![0xfb, 0xff], res.unwrap());
+        let res = base64url_decode_percent("TWFu");
+        assert_eq!("Man".as_bytes(), res.unwrap().as_slice());
+        for malformed in ["TWF%", "TW%2", "TW%G1u", "TW%+1u"] {
+            assert!(base64url_decode_percent(malformed).is_err());
+        }
+    }
+
+    #[test]
+    fn when_try_decode_malformed_input_should_not_panic() {
+        for input in ["", "T", "TW", "TWF", "TWFuT", "=", "====", "TWF=====", "TQ==TWFu", "TQ=A",
+                      "\u{ff}\u{0}AA", "TWFu\n"] {
+            let _ = try_decode(input.as_bytes());
+        }
+        assert!(try_decode("".as_bytes()).unwrap().is_empty());
+        assert!(try_decode("TWF".as_bytes()).is_err());
+        assert!(try_decode("====".as_bytes()).is_err());
+        assert!(try_decode(&[0xff, 0, b'A', b'A']).is_err());
+    }
+
+    #[test]
+    fn when_try_decode_random_input_should_not_panic() {
+        // same property as the decode fuzz target, with a fixed xorshift seed
+        let charset = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/=-_ \n\u{0}\u{7f}".as_bytes();
+        let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+        for _ in 0..20_000 {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            let len = (state % 17) as usize;
+            let input: Vec<u8> = (0..len).map(|i| charset[((state >> (i * 3)) % charset.len() as u64) as usize])
+                .collect();
+            if let Ok(decoded) = try_decode(&input) {
+                assert!(decoded.len() <= input.len() / 4 * 3);
+            }
+        }
+    }
+
+    #[test]
+    fn when_try_encode_should_round_trip_with_try_decode() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        for i in 0..bytes.len() {
+            let encoded = try_encode(&bytes[..i]).unwrap();
+            assert_eq!(&bytes[..i], try_decode(&encoded).unwrap().as_slice());
+        }
+    }
+
+    #[test]
+    fn when_decode_csv_column_should_decode_selected_cell() {
+        let row = "1, TWFu ,ignored";
+        assert_eq!("Man".as_bytes(), decode_csv_column(row, ',', 1).unwrap().as_slice());
+        assert_eq!("Column 3 out of range", decode_csv_column(row, ',', 3).unwrap_err().msg);
+        assert!(decode_csv_column("1;;x", ';', 1).unwrap().is_empty());
+        assert!(decode_csv_column(row, ';', 1).is_err());
+    }
+
+    #[test]
+    fn when_base64_decode_max_exceeded_should_fail() {
+        let input = [b'A'; 100];
+        let res = base64_decode_max(&input, 10);
+        assert_eq!("Decoded length 75 exceeds the maximum of 10", res.unwrap_err().msg);
+        assert_eq!(75, base64_decode_max(&input, 75).unwrap().len());
+        assert_eq!("Man".as_bytes(), base64_decode_max("TWFu".as_bytes(), 3).unwrap().as_slice());
+        assert_eq!(1, base64_decode_max("TQ==".as_bytes(), 1).unwrap().len());
+    }
+
+    #[test]
+    fn when_base64_decode_partial_should_leave_incomplete_group() {
+        let (decoded, consumed) = base64_decode_partial("TWFuTW".as_bytes()).unwrap();
+        assert_eq!("Man".as_bytes(), decoded.as_slice());
+        assert_eq!(4, consumed);
+        let (decoded, consumed) = base64_decode_partial("TWFuTWE=".as_bytes()).unwrap();
+        assert_eq!("ManMa".as_bytes(), decoded.as_slice());
+        assert_eq!(8, consumed);
+        let (decoded, consumed) = base64_decode_partial("TWF".as_bytes()).unwrap();
+        assert!(decoded.is_empty());
+        assert_eq!(0, consumed);
+    }
+
+    #[test]
+    fn when_default_flavour_should_be_standard() {
+        let res = base64_encode("Is it ~~~?".to_string(), &Flavour::default());
+        assert_eq!("SXMgaXQgfn5+Pw==", res.unwrap());
+        assert_eq!("SXMgaXQgfn5+Pw==".as_bytes(), try_encode("Is it ~~~?".as_bytes()).unwrap().as_slice());
+    }
+
+    #[test]
+    fn when_decode_truncated_stream_should_report_it() {
+        let encoded = base64_encode_bytes("A stream which was cut".as_bytes(), &Base64Standard);
+        assert_eq!(32, encoded.len());
+        let res = base64_decode_bytes(&encoded[..29], &Base64Standard);
+        assert_eq!(Some(DecodeError::InvalidLength), res.unwrap_err().decode_error);
+        let res = base64_decode_bytes(&encoded[..30], &Base64Standard);
+        assert_eq!(Some(DecodeError::InvalidPadding), res.unwrap_err().decode_error);
+        let res = base64_decode_bytes("TWE".as_bytes(), &Base64Standard);
+        assert_eq!(Some(DecodeError::InvalidPadding), res.unwrap_err().decode_error);
+    }
+
+    #[test]
+    fn when_canonicalize_should_normalize_trailing_bits() {
+        let engine = Base64Engine::standard();
+        assert_eq!("TQ==", canonicalize("TR==", &engine).unwrap());
+        assert_eq!("TQ==", canonicalize("TQ==", &engine).unwrap());
+        assert_eq!("TWE=", canonicalize("TWF=", &engine).unwrap());
+        let url_safe = Base64Engine::url_safe().with_pad(b'~').unwrap();
+        assert_eq!("-_8~", canonicalize("-_9~", &url_safe).unwrap());
+        assert!(canonicalize("TR=", &engine).is_err());
+    }
+
+    #[test]
+    fn when_base64_encode_framed_should_wrap_encoding() {
+        let framed = base64_encode_framed("Man and woman".as_bytes(), "<<<", ">>>");
+        assert_eq!("<<<TWFuIGFuZCB3b21hbg==>>>", framed);
+        assert_eq!(framed.len(), framed.capacity());
+        let middle = &framed[3..framed.len() - 3];
+        assert_eq!("Man and woman".as_bytes(), try_decode(middle.as_bytes()).unwrap().as_slice());
+        assert_eq!("", base64_encode_framed(&[], "", ""));
+    }
+
+    #[test]
+    fn when_base64_decode_framed_should_check_affixes() {
+        let decoded = base64_decode_framed("<<<TWFuIGFuZCB3b21hbg==>>>", "<<<", ">>>");
+        assert_eq!("Man and woman".as_bytes(), decoded.unwrap().as_slice());
+        let res = base64_decode_framed("<<<TWFuIGFuZCB3b21hbg==", "<<<", ">>>");
+        assert_eq!("Missing suffix \">>>\"", res.unwrap_err().msg);
+        assert!(base64_decode_framed("TWFu>>>", "<<<", ">>>").is_err());
+        assert!(base64_decode_framed("<<>>", "<<<", ">>>").is_err());
+    }
+
+    #[test]
+    fn when_encode_calc_byte_size_without_padding_should_drop_pad_chars() {
+        let padded = [4, 4, 4, 8, 8, 8];
+        let unpadded = [2, 3, 4, 6, 7, 8];
+        for len in 1..=6 {
+            let bytes = vec![0; len];
+            assert_eq!(padded[len - 1], encode_calc_byte_size(&bytes, true));
+            assert_eq!(unpadded[len - 1], encode_calc_byte_size(&bytes, false));
+            assert_eq!(unpadded[len - 1], Base64Engine::standard().no_pad().encode(&bytes).len());
+        }
+    }
+
+    #[test]
+    fn when_base64_decode_mixed_should_accept_both_alphabets() {
+        let bytes = [0xfb, 0xff, 0xbf, 0xfb, 0xef];
+        assert_eq!("+/+/++8=", str::from_utf8(&base64_encode_bytes(&bytes, &Base64Standard)).unwrap());
+        for input in ["+/+/++8=", "-_-_--8=", "-/+_-+8="] {
+            assert_eq!(bytes.to_vec(), base64_decode_mixed(input.as_bytes()).unwrap());
+        }
+        assert!(base64_decode_mixed("-/+_-+8.".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn when_decoded_len_checked_should_validate_without_decoding() {
+        assert_eq!(0, base64_decoded_len_checked(b"").unwrap());
+        assert_eq!(3, base64_decoded_len_checked(b"TWFu").unwrap());
+        assert_eq!(5, base64_decoded_len_checked(b"TWFuTWE=").unwrap());
+        assert_eq!(4, base64_decoded_len_checked(b"TWFuTQ==").unwrap());
+        assert_eq!("Invalid character 0x21 at position 2", base64_decoded_len_checked(b"TW!u").unwrap_err().msg);
+        assert_eq!("Invalid character 0x3d at position 2", base64_decoded_len_checked(b"TW=uTWFu").unwrap_err().msg);
+        assert!(base64_decoded_len_checked(b"TWF").is_err());
+        assert!(base64_decoded_len_checked(b"TWFuT").is_err());
+    }
+
+    #[test]
+    fn when_decode_repair_should_restore_single_missing_pad() {
+        assert_eq!(("Ma".as_bytes().to_vec(), true), base64_decode_repair(b"TWE").unwrap());
+        assert_eq!(("M".as_bytes().to_vec(), true), base64_decode_repair(b"TQ=").unwrap());
+        assert_eq!(("Ma".as_bytes().to_vec(), false), base64_decode_repair(b"TWE=").unwrap());
+        assert!(base64_decode_repair(b"TQ").is_err());
+        assert!(base64_decode_repair(b"TWFuT").is_err());
+    }
+
+    #[test]
+    fn when_encode_to_writer_should_write_encoded_chunks() {
+        let bytes: Vec<u8> = (0..10_000u32).map(|i| (i * 7 % 256) as u8).collect();
+        let mut out = Vec::new();
+        let written = base64_encode_to_writer(&bytes, &mut out).unwrap();
+        assert_eq!(base64_encode_bytes(&bytes, &Flavour::default()), out);
+        assert_eq!(out.len(), written);
+        assert!(base64_encode_to_stdout(&[]).is_ok());
+    }
+
+    #[test]
+    fn when_encode_to_file_should_match_encode_bytes() {
+        let target = std::env::temp_dir().join("simple_base64_encode_to_file.txt");
+        let written = base64_encode_to_file("resources/sample_image.png", &target, &Base64Url).unwrap();
+        let expected = base64_encode_bytes(&fs::read("resources/sample_image.png").unwrap(), &Base64Url);
+        assert_eq!(expected.len(), written);
+        assert_eq!(expected, fs::read(&target).unwrap());
+        fs::remove_file(target).unwrap();
+    }
+
+    #[test]
+    fn when_decode_to_writer_should_write_decoded_chunks() {
+        let bytes: Vec<u8> = (0..10_000u32).map(|i| (i * 13 % 256) as u8).collect();
+        let encoded = base64_encode_bytes(&bytes, &Flavour::default());
+        let mut out = Vec::new();
+        assert_eq!(bytes.len(), base64_decode_to_writer(&encoded, &mut out).unwrap());
+        assert_eq!(bytes, out);
+        let mut out = Vec::new();
+        assert!(base64_decode_to_writer(b"TWFuTW!=", &mut out).is_err());
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn when_only_padding_should_fail() {
+        for input in ["====", "=", "========"] {
+            let res = base64_decode_bytes(input.as_bytes(), &Base64Standard);
+            assert_eq!(Some(DecodeError::InvalidPadding), res.unwrap_err().decode_error);
+        }
+        assert!(try_decode(b"TQ======").is_err());
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn when_decode_universal_should_accept_both_alphabets() {
+        let payload = [0xfb, 0xff, 0xbf, 0x3e, 0x00];
+        let standard = base64_encode_bytes(&payload, &Base64Standard);
+        let url = base64_encode_bytes(&payload, &Base64Url);
+        assert_ne!(standard, url);
+        assert_eq!(payload.to_vec(), base64_decode_universal(&standard).unwrap());
+        assert_eq!(payload.to_vec(), base64_decode_universal(&url).unwrap());
+        assert_eq!("Invalid character 0x2e at position 1", base64_decode_universal(b"+.__").unwrap_err().msg);
+    }
+
+    #[test]
+    fn when_decode_with_bom_should_skip_it() {
+        assert_eq!("Man".as_bytes(), base64_decode_bytes(b"\xef\xbb\xbfTWFu", &Base64Standard).unwrap().as_slice());
+        assert_eq!("Man", base64_decode("\u{feff}TWFu".to_string(), &Base64Url).unwrap());
+        assert!(base64_decode_bytes(b"TW\xef\xbb\xbfFu", &Base64Standard).is_err());
+        assert!(base64_decode_bytes(b"\xef\xbbTWFu", &Base64Standard).is_err());
+    }
+
+    #[test]
+    fn when_decode_from_file_or_to_writer_with_bom_should_skip_it() {
+        let mut written = Vec::new();
+        assert_eq!(3, base64_decode_to_writer(b"\xef\xbb\xbfTWFu", &mut written).unwrap());
+        assert_eq!("Man".as_bytes(), written.as_slice());
+        assert_eq!("Man".as_bytes(), try_decode(b"\xef\xbb\xbfTWFu").unwrap().as_slice());
+        let source = std::env::temp_dir().join("simple_base64_bom.txt");
+        let target = std::env::temp_dir().join("simple_base64_bom.bin");
+        fs::write(&source, b"\xef\xbb\xbfTWFu").unwrap();
+        assert_eq!(3, base64_decode_from_file(&source, &target, &Base64Standard).unwrap());
+        assert_eq!("Man".as_bytes(), fs::read(&target).unwrap().as_slice());
+        fs::remove_file(source).unwrap();
+        fs::remove_file(target).unwrap();
+    }
+
+    #[test]
+    fn when_fixed_width_should_pad_to_width_and_decode_back() {
+        let encoded = base64_encode_bytes_fixed_width("Man".as_bytes(), 8).unwrap();
+        assert_eq!("TWFu====".as_bytes(), encoded.as_slice());
+        assert_eq!("Man".as_bytes(), base64_decode_bytes_fixed_width(&encoded).unwrap().as_slice());
+        let encoded = base64_encode_bytes_fixed_width("Ma".as_bytes(), 10).unwrap();
+        assert_eq!("TWE=======".as_bytes(), encoded.as_slice());
+        assert_eq!("Ma".as_bytes(), base64_decode_bytes_fixed_width(&encoded).unwrap().as_slice());
+        assert_eq!("TWFu".as_bytes(), base64_encode_bytes_fixed_width("Man".as_bytes(), 4).unwrap().as_slice());
+        assert!(base64_decode_bytes_fixed_width(b"========").unwrap().is_empty());
+        assert_eq!("Encoded length 8 exceeds the width of 6",
+                   base64_encode_bytes_fixed_width("ManMan".as_bytes(), 6).unwrap_err().msg);
+    }
+
+    #[test]
+    fn when_padding_count_wrong_should_fail() {
+        let res = base64_decode_bytes("TWFu==".as_bytes(), &Base64Standard);
+        assert_eq!(Some(DecodeError::InvalidPadding), res.unwrap_err().decode_error);
+        let res = base64_decode_bytes("TWE==".as_bytes(), &Base64Standard);
+        assert_eq!(Some(DecodeError::InvalidPadding), res.unwrap_err().decode_error);
+        let res = base64_decode_bytes("TQ=".as_bytes(), &Base64Standard);
+        assert_eq!(Some(DecodeError::InvalidPadding), res.unwrap_err().decode_error);
+        let res = base64_decode_bytes("TQ==TWFu".as_bytes(), &Base64Standard);
+        assert_eq!(Some(DecodeError::InvalidPadding), res.unwrap_err().decode_error);
+        let res = base64_decode_bytes("TW=uTWF=".as_bytes(), &Base64Standard);
+        assert_eq!(Some(DecodeError::InvalidByte { position: 2, byte: b'=' }), res.unwrap_err().decode_error);
+        assert!(base64_decode_bytes("TWE=".as_bytes(), &Base64Standard).is_ok());
+    }
+
+    #[test]
+    fn when_data_follows_a_padded_group_every_decoder_should_report_it_alike() {
+        let input = "TQ==TWFu";
+        let mut in_place = input.as_bytes().to_vec();
+        let mut reader = Base64Reader::with_flavour(input.as_bytes(), &Base64Standard);
+        let errors = vec![
+            base64_decode_bytes(input.as_bytes(), &Base64Standard).unwrap_err(),
+            base64_decode_in_place(&mut in_place).unwrap_err(),
+            base64_decode_to_writer(input.as_bytes(), Vec::new()).unwrap_err(),
+            Decoder::new(Base64Engine::standard()).decode(input.as_bytes(), &mut Vec::new()).unwrap_err(),
+            Base64DecodeIter::with_flavour(input.bytes(), &Base64Standard).collect::<Result<Vec<u8>, _>>().unwrap_err(),
+            base64_decode_lines(["TQ=", "=TWFu"]).unwrap_err(),
+            *reader.read_to_end(&mut Vec::new()).unwrap_err().into_inner().unwrap().downcast::<Base64Error>().unwrap(),
+            #[cfg(feature = "rayon")]
+            base64_decode_bytes_parallel(input.as_bytes()).unwrap_err(),
+        ];
+        for error in errors {
+            assert_eq!("Padded group ending at position 3 is followed by more data", error.msg);
+            assert_eq!(Some(DecodeError::InvalidPadding), error.decode_error);
+        }
+    }
+
+    #[test]
+    fn when_padding_completes_a_single_character_should_fail_as_truncated() {
+        let res = base64_decode_bytes("TWFuT===".as_bytes(), &Base64Standard);
+        let error = res.unwrap_err();
+        assert_eq!("Truncated input: final group at position 4 has a single character", error.msg);
+        assert_eq!(Some(DecodeError::InvalidLength), error.decode_error);
+        let mut out = [0; 6];
+        assert_eq!(error.msg, decode_into(b"TWFuT===", Base64Standard.engine(), &mut out).unwrap_err().msg);
+        assert!(base64_decoded_len_checked("TWFu==".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn when_decode_in_place_should_truncate_to_decoded() {
+        let mut buf = "TWFuTWFu".as_bytes().to_vec();
+        base64_decode_in_place(&mut buf).unwrap();
+        assert_eq!("ManMan".as_bytes(), buf.as_slice());
+        let mut buf = "TWFuTQ==".as_bytes().to_vec();
+        base64_decode_in_place(&mut buf).unwrap();
+        assert_eq!("ManM".as_bytes(), buf.as_slice());
+        let mut buf = "TWFuT!==".as_bytes().to_vec();
+        assert!(base64_decode_in_place(&mut buf).is_err());
+        assert_eq!("TWFuT!==".as_bytes(), buf.as_slice());
+    }
+
+    #[test]
+    fn when_decode_from_file_malformed_should_report_cause() {
+        let source = std::env::temp_dir().join("simple_base64_malformed.txt");
+        let target = std::env::temp_dir().join("simple_base64_malformed.bin");
+        fs::write(&source, "TWFu==").unwrap();
+        let error = base64_decode_from_file(&source, &target, &Base64Standard).unwrap_err();
+        assert_eq!(ErrorKind::InvalidData, error.kind());
+        assert_eq!("Expected 0 padding characters after 4 data characters, found 2", error.to_string());
+        let inner = error.into_inner().unwrap().downcast::<Base64Error>().unwrap();
+        assert_eq!("Expected 0 padding characters after 4 data characters, found 2", inner.msg);
+        assert!(!target.exists());
+        fs::remove_file(source).unwrap();
+    }
+
+    #[test]
+    fn when_base64url_decode_nopad_should_decode_jwt_segments() {
+        let header = base64url_decode_nopad(b"eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9").unwrap();
+        assert_eq!(r#"{"alg":"HS256","typ":"JWT"}"#, str::from_utf8(&header).unwrap());
+        let payload = base64url_decode_nopad(b"eyJzdWIiOiIxMjM0NTY3ODkwIiwibmFtZSI6IkpvaG4gRG9lIiwiaWF0IjoxNTE2MjM5MDIyfQ").unwrap();
+        assert_eq!(r#"{"sub":"1234567890","name":"John Doe","iat":1516239022}"#, str::from_utf8(&payload).unwrap());
+        assert_eq!(vec![0xfb, 0xff], base64url_decode_nopad(b"-_8").unwrap());
+        assert!(base64url_decode_nopad(b"-_8=").is_err());
+        assert!(base64url_decode_nopad(b"+/8").is_err());
+    }
+
+    #[test]
+    fn when_significant_char_count_should_skip_padding_and_whitespace() {
+        let wrapped = Base64Engine::standard().with_wrap(4, LineEnding::CrLf).encode("ManMa".as_bytes());
+        assert_eq!("TWFu\r\nTWE=".as_bytes(), wrapped.as_slice());
+        assert_eq!(7, significant_char_count(&wrapped, true));
+        assert_eq!(9, significant_char_count(&wrapped, false));
+        assert_eq!(0, significant_char_count(b"==", true));
+    }
+
+    #[test]
+    fn when_decode_bytes_should_agree_with_other_decoders() {
+        let mut state: u32 = 0x9e37_79b9;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state
+        };
+        let mut inputs: Vec<Vec<u8>> = ["", "TQ==", "TWE=", "TWFu", "QXNzdW1pbmcgTWFu", "+/+/", "AAAA", "TWFu=", "TW=u", "TQ=="]
+            .iter().map(|s| s.as_bytes().to_vec()).collect();
+        for _ in 0..2000 {
+            let bytes: Vec<u8> = (0..next() % 40).map(|_| next() as u8).collect();
+            inputs.push(base64_encode_bytes(&bytes, &Base64Standard));
+            let alphabet = b"TWFuQXN=+/a!";
+            inputs.push((0..next() % 12).map(|_| alphabet[next() as usize % alphabet.len()]).collect());
+        }
+        for input in inputs {
+            let decoded = base64_decode_bytes(&input, &Base64Standard).ok();
+            let mut out = vec![0; input.len()];
+            let decoded_into = decode_into(&input, Base64Standard.engine(), &mut out).ok().map(|size| out[..size].to_vec());
+            let iterated = Base64DecodeIter::with_flavour(input.iter().copied(), &Base64Standard).collect::<Result<Vec<u8>, _>>().ok();
+            assert_eq!(decoded_into, decoded, "{:?}", str::from_utf8(&input));
+            assert_eq!(iterated, decoded, "{:?}", str::from_utf8(&input));
+        }
+    }
+
+    #[test]
+    fn when_encode_and_checksum_should_hash_input() {
+        use std::collections::hash_map::DefaultHasher;
+
+        let bytes: Vec<u8> = (0..10_000u32).map(|i| (i * 11 % 256) as u8).collect();
+        let mut hasher = DefaultHasher::new();
+        let encoded = base64_encode_and_checksum(&bytes, &mut hasher);
+        assert_eq!(base64_encode_bytes(&bytes, &Flavour::default()), encoded);
+        let mut expected = DefaultHasher::new();
+        expected.write(&bytes);
+        assert_eq!(expected.finish(), hasher.finish());
+        assert!(base64_encode_and_checksum(&[], &mut DefaultHasher::new()).is_empty());
+    }
+
+    #[test]
+    fn when_alphabet_passed_per_call_should_round_trip() {
+        let alphabet = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+        let encoded = base64_encode_bytes_alphabet(&[0xfb, 0xff], alphabet, Some(b'='));
+        assert_eq!(base64_encode_bytes(&[0xfb, 0xff], &Base64Url), encoded);
+        assert_eq!(vec![0xfb, 0xff], base64_decode_bytes_alphabet(&encoded, alphabet, Some(b'=')).unwrap());
+        assert_eq!("-_8".as_bytes(), base64_encode_bytes_alphabet(&[0xfb, 0xff], alphabet, None).as_slice());
+        assert_eq!(vec![0xfb, 0xff], base64_decode_bytes_alphabet(b"-_8", alphabet, None).unwrap());
+        assert!(base64_decode_bytes_alphabet(b"-_8=", alphabet, Some(b'-')).is_err());
+        assert!(base64_decode_bytes_alphabet(b"AAAA", &[b'A'; 64], None).is_err());
+    }
+
+    #[test]
+    fn when_data_follows_padded_group_should_fail() {
+        let res = base64_decode_bytes("TWE=TWFu".as_bytes(), &Base64Standard);
+        assert_eq!(Some(DecodeError::InvalidPadding), res.unwrap_err().decode_error);
+        let res = base64_decode_bytes("TWFuTWE=TWE=".as_bytes(), &Base64Standard);
+        assert_eq!(Some(DecodeError::InvalidPadding), res.unwrap_err().decode_error);
+        assert!(try_decode(b"TWE=TWFu").is_err());
+        assert!(base64_decoded_len_checked(b"TWE=TWFu").is_err());
+    }
+
+    #[test]
+    fn when_encode_slice_to_string_should_match_encode() {
+        let text = "Assuming Man ".repeat(100);
+        let encoded = base64_encode_slice_to_string(text.as_bytes());
+        assert_eq!(encoded_len(text.len()), encoded.capacity());
+        assert_eq!(base64_encode(text, &Flavour::default()).unwrap(), encoded);
+        assert_eq!("", base64_encode_slice_to_string(&[]));
+    }
+
+    #[test]
+    fn when_decoded_equal_should_ignore_alphabet_and_padding() {
+        let url_no_pad = String::from_utf8(URL_SAFE_NO_PAD.encode("Man".as_bytes())).unwrap();
+        assert!(decoded_equal("TWFu", &url_no_pad).unwrap());
+        assert!(decoded_equal("TWE=", "TWE").unwrap());
+        assert!(decoded_equal("-_8", "+/8=").unwrap());
+        assert!(!decoded_equal("TWFu", "TWE=").unwrap());
+        assert!(decoded_equal("TWFu", "TW!u").is_err());
+    }
+
+    #[test]
+    fn when_encode_streaming_should_produce_bounded_chunks() {
+        let bytes: Vec<u8> = (0..1000u32).map(|i| (i * 17 % 256) as u8).collect();
+        for chunk_size in [1, 3, 4, 10, 64, 5000] {
+            let mut chunks: Vec<Vec<u8>> = Vec::new();
+            base64_encode_streaming(&bytes, chunk_size, |chunk| chunks.push(chunk.to_vec()));
+            assert!(chunks.iter().all(|chunk| !chunk.is_empty() && chunk.len() <= chunk_size));
+            assert_eq!(base64_encode_bytes(&bytes, &Flavour::default()), chunks.concat());
+        }
+        let mut called = false;
+        base64_encode_streaming(&[], 4, |_| called = true);
+        assert!(!called);
+    }
+
+    #[test]
+    fn when_checked_len_near_overflow_should_not_overflow() {
+        let max_input = usize::MAX / 4 * 3;
+        assert_eq!(Some(usize::MAX / 4 * 4), checked_encoded_len(max_input));
+        assert_eq!(None, checked_encoded_len(max_input + 1));
+        assert_eq!(None, checked_encoded_len(usize::MAX));
+        assert_eq!(Some(encoded_len(isize::MAX as usize)), checked_encoded_len(isize::MAX as usize));
+        assert_eq!(Some(0), checked_encoded_len(0));
+        assert_eq!(Some(usize::MAX / 4 * 3 + 2), checked_decoded_len(usize::MAX));
+        assert_eq!(None, checked_decoded_len(usize::MAX - 2));
+        assert_eq!(Some(2), checked_decoded_len(3));
+        assert_eq!(Some(3), checked_decoded_len(4));
+    }
+
+    #[test]
+    fn when_decode_lines_should_join_wrapped_lines() {
+        let lines = vec!["QXNzdW1p", "bmcgTW\r", "Fu"];
+        assert_eq!("Assuming Man".as_bytes(), base64_decode_lines(&lines).unwrap().as_slice());
+        let owned: Vec<String> = vec!["TWFu".to_string(), "".to_string(), "TWE=".to_string()];
+        assert_eq!("ManMa".as_bytes(), base64_decode_lines(owned).unwrap().as_slice());
+        assert!(base64_decode_lines(["TWE=", "TWFu"]).is_err());
+        assert!(base64_decode_lines(["TWF"]).is_err());
+    }
+
+    #[test]
+    fn when_decoding_fails_should_keep_the_kind_with_the_detailed_message() {
+        let error = try_decode("TWE".as_bytes()).unwrap_err();
+        assert_eq!("Missing padding: final group at position 0 has 3 characters", error.msg);
+        assert_eq!(Some(DecodeError::InvalidPadding), error.decode_error);
+        let error = base64_decode("TW!u".to_string(), &Base64Standard).unwrap_err();
+        assert_eq!("Decoding failed: Invalid character 0x21 at position 2", error.msg);
+        assert_eq!(Some(DecodeError::InvalidByte { position: 2, byte: b'!' }), error.decode_error);
+        assert_eq!(None, Base64Engine::standard().with_pad(b'A').unwrap_err().decode_error);
+    }
+
+    #[test]
+    fn when_input_is_aligned_should_encode_and_decode_like_unaligned_input() {
+        let data: Vec<u8> = (0..=255u8).rev().collect();
+        for flavour in [Base64Standard, Base64Url] {
+            for length in 0..=24 {
+                let encoded = base64_encode_bytes(&data[..length], &flavour);
+                let aligned = base64_encode_bytes(&data[..length - length % 3], &flavour);
+                assert!(encoded.starts_with(&aligned), "length {}", length);
+                assert_eq!(&data[..length], base64_decode_bytes(&encoded, &flavour).unwrap());
+                let unpadded = URL_SAFE_NO_PAD.encode(&data[..length]);
+                assert_eq!(&data[..length], base64url_decode_nopad(&unpadded).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn when_base64_encode_append_str_should_match_concatenated_encodings() {
+        let parts: [&[u8]; 4] = [b"Man", b"", b"Ma", b"M\xff\x00\x01"];
+        let mut out = String::from("data: ");
+        for part in parts {
+            base64_encode_append_str(part, &mut out);
+        }
+        let expected: String = parts.iter().map(|part| base64_encode_slice_to_string(part)).collect();
+        assert_eq!(format!("data: {}", expected), out);
+        assert_eq!("data: TWFuTWE=Tf8AAQ==", out);
+    }
+
+    #[test]
+    fn when_base64_decode_relaxed_should_accept_any_amount_of_missing_padding() {
+        for input in ["TWE", "TWE="] {
+            assert_eq!("Ma".as_bytes(), base64_decode_relaxed(input.as_bytes()).unwrap());
+        }
+        for input in ["TQ", "TQ=", "TQ=="] {
+            assert_eq!("M".as_bytes(), base64_decode_relaxed(input.as_bytes()).unwrap());
+        }
+        assert_eq!("Man".as_bytes(), base64_decode_relaxed("TWFu".as_bytes()).unwrap());
+        assert!(base64_decode_relaxed("".as_bytes()).unwrap().is_empty());
+        let res = base64_decode_relaxed("TWFuT".as_bytes());
+        assert_eq!("Truncated input: final group at position 4 has a single character", res.unwrap_err().msg);
+        assert!(base64_decode_relaxed("TWE==".as_bytes()).is_err());
+        assert!(base64_decode_relaxed("TQ=A".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn when_input_is_a_single_quartet_should_decode_it_once() {
+        for (input, expected) in [("TWFu", "Man"), ("TWE=", "Ma"), ("TQ==", "M")] {
+            let expected = expected.as_bytes();
+            assert_eq!(expected, base64_decode_bytes(input.as_bytes(), &Base64Standard).unwrap());
+            let mut out = [0; 3];
+            let size = decode_into(input.as_bytes(), Base64Standard.engine(), &mut out).unwrap();
+            assert_eq!(expected, &out[..size]);
+            let mut buf = input.as_bytes().to_vec();
+            base64_decode_in_place(&mut buf).unwrap();
+            assert_eq!(expected, buf);
+            let iterated: Result<Vec<u8>, _> = Base64DecodeIter::with_flavour(input.bytes(), &Base64Standard).collect();
+            assert_eq!(expected, iterated.unwrap());
+        }
+    }
+
+    #[test]
+    fn when_base64_decode_cow_should_match_the_owned_decode() {
+        for input in ["", "TWFu", "TWFuTWE=", "TQ=="] {
+            let decoded = base64_decode_cow(input.as_bytes()).unwrap();
+            assert_eq!(try_decode(input.as_bytes()).unwrap(), decoded.as_ref());
+        }
+        assert_eq!("Invalid character 0x21 at position 0", base64_decode_cow(b"!WFu").unwrap_err().msg);
+    }
+
+    #[test]
+    fn when_asked_for_pad_char_should_report_the_one_in_use() {
+        assert_eq!(b'=', default_pad_char());
+        assert_eq!(Some(b'='), active_pad_char(Flavour::default().engine()));
+        assert_eq!(Some(b'.'), active_pad_char(&Base64Engine::standard().with_pad(b'.').unwrap()));
+        assert_eq!(None, active_pad_char(&Base64Engine::lexicographic()));
+    }
+
+    #[test]
+    fn when_decode_from_json_string_should_resolve_escapes() {
+        // "+/8=" decodes to fb ff
+        assert_eq!(vec![0xfb, 0xff], decode_from_json_string(r#""+\/8=""#).unwrap());
+        assert_eq!(vec![0xfb, 0xff], decode_from_json_string(r"+\u002f8\u003D").unwrap());
+        assert_eq!(vec![0xfb, 0xff], decode_from_json_string("-_8=").unwrap());
+        assert_eq!("ManMa".as_bytes(), decode_from_json_string(r"TWFu\r\nTWE=").unwrap());
+        assert_eq!("Malformed JSON escape at position 4", decode_from_json_string(r"TWFu\x").unwrap_err().msg);
+        let res = decode_from_json_string(r"TWFu\u00e9");
+        assert_eq!("Malformed or non ASCII \\u escape at position 4", res.unwrap_err().msg);
+        assert_eq!("Invalid character 0x09 at position 4", decode_from_json_string(r"TWFu\t").unwrap_err().msg);
+    }
+
+    #[test]
+    fn when_encoding_with_progress_should_report_each_chunk_up_to_the_total() {
+        let bytes = vec![7; 10_000];
+        let mut reports = Vec::new();
+        let mut out = Vec::new();
+        base64_encode_to_writer_with_progress(&bytes, &mut out, |processed, total| reports.push((processed, total))).unwrap();
+        assert_eq!(base64_encode_slice_to_string(&bytes).as_bytes(), out);
+        assert_eq!(vec![(3072, 10_000), (6144, 10_000), (9216, 10_000), (10_000, 10_000)], reports);
+
+        let mut last = 0;
+        let target = std::env::temp_dir().join("simple_base64_progress.txt");
+        let res = base64_encode_to_file_with_progress("resources/sample_image.png", &target, &Base64Standard,
+                                                      |processed, total| {
+            assert!(processed > last && processed <= total);
+            last = processed;
+        });
+        assert!(res.is_ok());
+        assert_eq!(fs::metadata("resources/sample_image.png").unwrap().len() as usize, last);
+        fs::remove_file(target).unwrap();
+    }
+
+    #[test]
+    fn when_encoding_empty_input_with_progress_should_report_completion_once() {
+        let mut reports = Vec::new();
+        let mut out = Vec::new();
+        base64_encode_to_writer_with_progress(&[], &mut out, |processed, total| reports.push((processed, total))).unwrap();
+        assert!(out.is_empty());
+        assert_eq!(vec![(0, 0)], reports);
+    }
+
+    #[test]
+    fn when_base64_decode_bytes_canonical_should_flag_non_canonical_input() {
+        assert_eq!(("M".as_bytes().to_vec(), true), base64_decode_bytes_canonical(b"TQ==").unwrap());
+        assert_eq!(("M".as_bytes().to_vec(), false), base64_decode_bytes_canonical(b"TR==").unwrap());
+        assert_eq!(("M".as_bytes().to_vec(), false), base64_decode_bytes_canonical(b"TQ").unwrap());
+        assert_eq!(("Man".as_bytes().to_vec(), true), base64_decode_bytes_canonical(b"TWFu").unwrap());
+        assert!(base64_decode_bytes_canonical(b"TQ=A").is_err());
+    }
+
+    #[test]
+    fn when_encoding_overhead_should_count_padding_and_separators() {
+        assert_eq!(4.0 / 3.0, encoding_overhead(3, true, None));
+        assert_eq!(4.0 / 3.0, encoding_overhead(3, false, None));
+        assert_eq!(4.0, encoding_overhead(1, true, None));
+        assert_eq!(2.0, encoding_overhead(1, false, None));
+        assert_eq!(1.0, encoding_overhead(0, true, None));
+        // 57 bytes fill exactly one MIME line of 76 characters, 114 bytes two lines and a CRLF
+        assert_eq!(76.0 / 57.0, encoding_overhead(57, true, Some((76, 2))));
+        assert_eq!(154.0 / 114.0, encoding_overhead(114, true, Some((76, 2))));
+        let bytes = vec![0; 1000];
+        let wrapped = Base64Engine::standard().mime().encode(&bytes);
+        assert_eq!(wrapped.len() as f64 / 1000.0, encoding_overhead(1000, true, Some((76, 2))));
+    }
+
+    #[test]
+    fn when_validate_base64_file_should_report_the_offset_of_the_first_error() {
+        let dir = std::env::temp_dir();
+        let (valid, corrupted_path) = (dir.join("simple_base64_validate.txt"), dir.join("simple_base64_corrupted.txt"));
+        let (wrapped, truncated) = (dir.join("simple_base64_wrapped.txt"), dir.join("simple_base64_truncated.txt"));
+        base64_encode_to_file("resources/sample_image.png", &valid, &Base64Standard).unwrap();
+        assert!(validate_base64_file(&valid, &Base64Standard, false).is_ok());
+        let mut corrupted = fs::read(&valid).unwrap();
+        corrupted[10_000] = b'*';
+        corrupted.extend_from_slice(b"\n");
+        fs::write(&corrupted_path, &corrupted).unwrap();
+        let res = validate_base64_file(&corrupted_path, &Base64Standard, true);
+        assert_eq!("Invalid character 0x2a at position 10000", res.unwrap_err().msg);
+        fs::write(&wrapped, "TWFu\r\nTW E=\n").unwrap();
+        assert!(validate_base64_file(&wrapped, &Base64Standard, true).is_ok());
+        let res = validate_base64_file(&wrapped, &Base64Standard, false);
+        assert_eq!("Invalid character 0x0d at position 4", res.unwrap_err().msg);
+        fs::write(&truncated, "TWFuT===").unwrap();
+        let res = validate_base64_file(&truncated, &Base64Standard, false);
+        assert_eq!("Truncated input: final group at position 4 has a single character", res.unwrap_err().msg);
+        fs::write(&truncated, "TWFuTWE").unwrap();
+        let res = validate_base64_file(&truncated, &Base64Standard, false);
+        assert_eq!("Missing padding: final group at position 4 has 3 characters", res.unwrap_err().msg);
+        for path in [valid, corrupted_path, wrapped, truncated] {
+            fs::remove_file(path).unwrap();
+        }
+        let res = validate_base64_file(dir.join("simple_base64_missing.txt"), &Base64Standard, false);
+        assert!(res.unwrap_err().msg.starts_with("Read failed: "));
+    }
+
+    #[test]
+    fn when_base64_decode_budgeted_exceeded_should_fail() {
+        let res = base64_decode_budgeted(b"TWFuTWFuTWE=", 2);
+        assert_eq!("Input of 3 quartets exceeds the budget of 2", res.unwrap_err().msg);
+        assert_eq!("ManManMa".as_bytes(), base64_decode_budgeted(b"TWFuTWFuTWE=", 3).unwrap());
+        assert!(base64_decode_budgeted(b"TWFuTW", 1).is_err());
+        assert!(base64_decode_budgeted(b"", 0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn when_base64_decode_prefix_should_decode_only_the_first_bytes() {
+        let mut encoded = base64_encode_bytes(&[0x89, b'P', b'N', b'G', 0x0d, 0x0a], &Flavour::default());
+        // whatever follows the prefix is not even looked at
+        encoded.extend_from_slice(&[b'!'; 100_000]);
+        assert_eq!(vec![0x89, b'P', b'N'], base64_decode_prefix(&encoded, 3).unwrap());
+        assert_eq!(vec![0x89, b'P', b'N', b'G'], base64_decode_prefix(&encoded, 4).unwrap());
+        assert!(base64_decode_prefix(&encoded, 0).unwrap().is_empty());
+        assert!(base64_decode_prefix(&encoded, 7).is_err());
+        assert_eq!("Ma".as_bytes(), base64_decode_prefix(b"TWE=", 10).unwrap());
+    }
+
+    #[test]
+    fn when_base64_decode_padded_to_block_should_fill_the_last_block_with_zeros() {
+        let decoded = base64_decode_padded_to_block(b"TWFu", 16).unwrap();
+        assert_eq!(16, decoded.len());
+        assert_eq!("Man".as_bytes(), &decoded[..3]);
+        assert!(decoded[3..].iter().all(|&b| b == 0));
+        assert_eq!("Man".as_bytes(), base64_decode_padded_to_block(b"TWFu", 3).unwrap());
+        assert!(base64_decode_padded_to_block(b"", 16).unwrap().is_empty());
+        let res = base64_decode_padded_to_block(b"TWFu", 0);
+        assert_eq!("Block size must be greater than zero", res.unwrap_err().msg);
+    }
+
+    #[test]
+    fn when_detect_flavour_should_tell_by_the_62nd_and_63rd_characters() {
+        assert_eq!(Some(Base64Url), detect_flavour(b"-_8="));
+        assert_eq!(Some(Base64Url), detect_flavour(b"TWF_"));
+        assert_eq!(Some(Base64Standard), detect_flavour(b"+/8="));
+        assert_eq!(Some(Base64Standard), detect_flavour(b"TWF+"));
+        assert_eq!(None, detect_flavour(b"TWFuTWE="));
+        assert_eq!(None, detect_flavour(b"+_8="));
+        assert_eq!(None, detect_flavour(b""));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,34 @@
+//! Building blocks for custom parsers which handle the framing of the base64 themselves.
+
+use crate::errors::{Base64Error, Flavour};
+use crate::{decode_checked_quartet, invalid_character};
+
+/// Decodes a single complete quartet of the default flavour into its 3 bytes. Every character is
+/// checked, so padding, which would leave fewer bytes, is rejected like any other invalid character.
+///
+/// ```
+/// use simple_base64::low_level::decode_one_quartet;
+///
+/// assert_eq!(*b"Man", decode_one_quartet(b"TWFu").unwrap());
+/// ```
+pub fn decode_one_quartet(input: &[u8; 4]) -> Result<[u8; 3], Base64Error> {
+    let table = Flavour::default().engine().decode_table();
+    let converted = input.map(|b| table[b as usize]);
+    decode_checked_quartet(converted, false)
+        .map(|(decoded, _)| decoded)
+        .map_err(|i| invalid_character(input[i], i))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn when_decode_one_quartet_should_validate_each_character() {
+        assert_eq!(*b"Man", decode_one_quartet(b"TWFu").unwrap());
+        assert_eq!([0, 0, 0], decode_one_quartet(b"AAAA").unwrap());
+        assert_eq!("Invalid character 0x21 at position 2", decode_one_quartet(b"TW!u").unwrap_err().msg);
+        assert_eq!("Invalid character 0x3d at position 3", decode_one_quartet(b"TWE=").unwrap_err().msg);
+        assert_eq!("Invalid character 0x0a at position 0", decode_one_quartet(b"\nTWF").unwrap_err().msg);
+    }
+}
\ No newline at end of file
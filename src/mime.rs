@@ -0,0 +1,74 @@
+use crate::engine::Base64Engine;
+use crate::errors::{Base64Error, DecodeError};
+use crate::hex_value;
+
+/// Decodes an email body according to its `Content-Transfer-Encoding` header (RFC 2045 section 6):
+/// `base64`, `quoted-printable`, or one of the identity encodings `7bit`, `8bit` and `binary`.
+/// The header value is matched case insensitively.
+pub fn decode_content_transfer(encoding: &str, body: &[u8]) -> Result<Vec<u8>, Base64Error> {
+    match encoding.trim().to_ascii_lowercase().as_str() {
+        "base64" => Base64Engine::standard().mime().decode(body),
+        "quoted-printable" => decode_quoted_printable(body),
+        "7bit" | "8bit" | "binary" => Ok(body.to_vec()),
+        _ => Err(Base64Error::malformed(DecodeError::InvalidFormat,
+                                        &format!("Unsupported content transfer encoding '{}'", encoding)))
+    }
+}
+
+/// Replaces `=XX` escapes with their byte and drops the soft line breaks `=\r\n` and `=\n`.
+fn decode_quoted_printable(body: &[u8]) -> Result<Vec<u8>, Base64Error> {
+    let mut res = Vec::with_capacity(body.len());
+    let mut i = 0;
+    while i < body.len() {
+        if body[i] != b'=' {
+            res.push(body[i]);
+            i += 1;
+            continue;
+        }
+        match &body[i + 1..] {
+            [b'\r', b'\n', ..] => i += 3,
+            [b'\n', ..] => i += 2,
+            [high, low, ..] => match (hex_value(*high), hex_value(*low)) {
+                (Some(high), Some(low)) => {
+                    res.push(high << 4 | low);
+                    i += 3;
+                }
+                _ => return Err(malformed_escape(i))
+            },
+            _ => return Err(malformed_escape(i))
+        }
+    }
+    Ok(res)
+}
+
+fn malformed_escape(position: usize) -> Base64Error {
+    Base64Error::malformed(DecodeError::InvalidByte { position, byte: b'=' },
+                           &format!("Malformed quoted-printable escape at position {}", position))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn when_base64_should_decode_mime_lines() {
+        let decoded = decode_content_transfer("Base64", b"SGVsbG8s\r\nIHdvcmxk\r\nIQ==\r\n").unwrap();
+        assert_eq!("Hello, world!".as_bytes(), decoded.as_slice());
+    }
+
+    #[test]
+    fn when_quoted_printable_should_decode_escapes_and_soft_breaks() {
+        let decoded = decode_content_transfer("quoted-printable", b"Caf=C3=A9 au lait, tr=\r\nop ch=\naud =3D 1").unwrap();
+        assert_eq!("Café au lait, trop chaud = 1", String::from_utf8(decoded).unwrap());
+        assert_eq!("Malformed quoted-printable escape at position 3",
+                   decode_content_transfer("quoted-printable", b"Caf=G9").unwrap_err().msg);
+        assert!(decode_content_transfer("quoted-printable", b"end=").is_err());
+    }
+
+    #[test]
+    fn when_other_encoding_should_pass_through_or_fail() {
+        assert_eq!(b"plain".to_vec(), decode_content_transfer(" 7bit ", b"plain").unwrap());
+        assert_eq!("Unsupported content transfer encoding 'x-uuencode'",
+                   decode_content_transfer("x-uuencode", b"").unwrap_err().msg);
+    }
+}
\ No newline at end of file
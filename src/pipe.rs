@@ -0,0 +1,87 @@
+use std::io::{self, Error, Read, Write};
+
+use crate::reader::Base64Reader;
+use crate::writer::Base64Writer;
+
+/// Encodes stdin to stdout with the default flavour, the whole of a tiny `base64` style binary.
+/// See [`pipe_encode_io`].
+pub fn pipe_encode() -> Result<(), Error> {
+    pipe_encode_io(io::stdin().lock(), io::stdout().lock())
+}
+
+/// Decodes stdin to stdout with the default flavour. See [`pipe_decode_io`].
+pub fn pipe_decode() -> Result<(), Error> {
+    pipe_decode_io(io::stdin().lock(), io::stdout().lock())
+}
+
+/// Streams `input` through a [`Base64Writer`] into `output`, ending the text with a newline as
+/// shell tools do.
+pub fn pipe_encode_io<R: Read, W: Write>(mut input: R, output: W) -> Result<(), Error> {
+    let mut writer = Base64Writer::new(output);
+    io::copy(&mut input, &mut writer)?;
+    let mut output = writer.finish()?;
+    output.write_all(b"\n")?;
+    output.flush()
+}
+
+/// Streams `input` through a [`Base64Reader`] into `output`. Line breaks are skipped, so the
+/// newline ending `echo` output or wrapped text decodes; anything else outside of the alphabet
+/// fails with `ErrorKind::InvalidData`.
+pub fn pipe_decode_io<R: Read, W: Write>(input: R, mut output: W) -> Result<(), Error> {
+    io::copy(&mut Base64Reader::new(SkipLineBreaks(input)), &mut output)?;
+    output.flush()
+}
+
+struct SkipLineBreaks<R: Read>(R);
+
+impl<R: Read> Read for SkipLineBreaks<R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        loop {
+            let read = self.0.read(buf)?;
+            let mut kept = 0;
+            for i in 0..read {
+                if buf[i] != b'\n' && buf[i] != b'\r' {
+                    buf[kept] = buf[i];
+                    kept += 1;
+                }
+            }
+            // a chunk of line breaks only is not the end of the stream
+            if kept > 0 || read == 0 {
+                return Ok(kept);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::ErrorKind;
+
+    use super::*;
+
+    #[test]
+    fn when_pipe_encode_io_should_write_a_line_of_base64() {
+        let mut output = Vec::new();
+        pipe_encode_io("ManMa".as_bytes(), &mut output).unwrap();
+        assert_eq!("TWFuTWE=\n".as_bytes(), output);
+    }
+
+    #[test]
+    fn when_pipe_decode_io_should_skip_line_breaks() {
+        let mut output = Vec::new();
+        pipe_decode_io("TWFu\r\nTWE=\n".as_bytes(), &mut output).unwrap();
+        assert_eq!("ManMa".as_bytes(), output);
+        let data: Vec<u8> = (0..=255u8).cycle().take(10_000).collect();
+        let mut encoded = Vec::new();
+        pipe_encode_io(data.as_slice(), &mut encoded).unwrap();
+        let mut decoded = Vec::new();
+        pipe_decode_io(encoded.as_slice(), &mut decoded).unwrap();
+        assert_eq!(data, decoded);
+    }
+
+    #[test]
+    fn when_pipe_decode_io_input_is_malformed_should_fail_with_invalid_data() {
+        let error = pipe_decode_io("TW u".as_bytes(), Vec::new()).unwrap_err();
+        assert_eq!(ErrorKind::InvalidData, error.kind());
+    }
+}
\ No newline at end of file
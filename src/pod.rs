@@ -0,0 +1,45 @@
+use std::mem::size_of;
+
+use bytemuck::Pod;
+
+use crate::errors::{Base64Error, DecodeError, Flavour};
+use crate::{base64_decode_bytes, base64_encode_bytes};
+
+/// Encodes the bytes of a slice of plain old data, e.g. `#[repr(C)]` structs, with the default
+/// flavour. The bytes are in native endianness.
+pub fn encode_pod_slice<T: Pod>(slice: &[T]) -> String {
+    let encoded = base64_encode_bytes(bytemuck::cast_slice(slice), &Flavour::default());
+    String::from_utf8(encoded).expect("base64 alphabets are ASCII")
+}
+
+/// Decodes a slice encoded by [`encode_pod_slice`], failing unless the decoded bytes fill a whole
+/// number of values.
+pub fn decode_pod_slice<T: Pod>(input: &[u8]) -> Result<Vec<T>, Base64Error> {
+    let decoded = base64_decode_bytes(input, &Flavour::default())?;
+    let size = size_of::<T>();
+    if size == 0 || !decoded.len().is_multiple_of(size) {
+        return Err(Base64Error::malformed(DecodeError::InvalidLength,
+                                          &format!("Decoded length {} is not a multiple of the value size {}",
+                                                   decoded.len(), size)));
+    }
+    // copied into a fresh Vec<T> as the decoded bytes may not be aligned for T
+    let mut res = vec![T::zeroed(); decoded.len() / size];
+    bytemuck::cast_slice_mut(&mut res).copy_from_slice(&decoded);
+    Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn when_pod_slice_should_round_trip() {
+        let values: [u32; 4] = [0, 1, 0xdead_beef, u32::MAX];
+        let encoded = encode_pod_slice(&values);
+        assert_eq!(24, encoded.len());
+        assert_eq!(values.to_vec(), decode_pod_slice::<u32>(encoded.as_bytes()).unwrap());
+        let error = decode_pod_slice::<u32>(b"TWFu").unwrap_err();
+        assert_eq!("Decoded length 3 is not a multiple of the value size 4", error.msg);
+        assert_eq!(Some(DecodeError::InvalidLength), error.decode_error);
+    }
+}
\ No newline at end of file
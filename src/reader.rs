@@ -0,0 +1,174 @@
+use std::io::{Error, Read};
+
+use crate::constants::{INVALID_CHAR, PADDING_CODE};
+use crate::engine::Base64Engine;
+use crate::errors::{Base64Error, Flavour};
+use crate::{data_after_padding, decode_checked_quartet, invalid_character, truncated};
+
+/// Decodes the base64 read from another reader, so a stream can be decoded with
+/// `std::io::copy`. Malformed input fails the read with `ErrorKind::InvalidData`.
+///
+/// ```
+/// use simple_base64::Base64Reader;
+///
+/// let mut decoded = Vec::new();
+/// std::io::copy(&mut Base64Reader::new("TWFuTWE=".as_bytes()), &mut decoded).unwrap();
+/// assert_eq!("ManMa".as_bytes(), decoded.as_slice());
+/// ```
+pub struct Base64Reader<R: Read> {
+    inner: R,
+    decoder: StreamDecoder,
+    decoded: Vec<u8>,
+    next: usize,
+    eof: bool,
+}
+
+impl<R: Read> Base64Reader<R> {
+    /// Decodes with the default flavour.
+    pub fn new(inner: R) -> Base64Reader<R> {
+        Base64Reader::with_flavour(inner, &Flavour::default())
+    }
+
+    pub fn with_flavour(inner: R, flavour: &Flavour) -> Base64Reader<R> {
+        Base64Reader { inner, decoder: StreamDecoder::new(flavour.engine()), decoded: Vec::new(), next: 0, eof: false }
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Reads the next chunk from the inner reader and decodes its complete quartets.
+    fn fill(&mut self) -> Result<(), Error> {
+        let mut chunk = [0; 4096];
+        let read = self.inner.read(&mut chunk)?;
+        self.decoded.clear();
+        self.next = 0;
+        if read == 0 {
+            self.eof = true;
+            return Ok(self.decoder.finish(&mut self.decoded)?);
+        }
+        Ok(self.decoder.push(&chunk[..read], &mut self.decoded)?)
+    }
+}
+
+impl<R: Read> Read for Base64Reader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        while self.next == self.decoded.len() {
+            if self.eof || buf.is_empty() {
+                return Ok(0);
+            }
+            self.fill()?;
+        }
+        let size = buf.len().min(self.decoded.len() - self.next);
+        buf[..size].copy_from_slice(&self.decoded[self.next..self.next + size]);
+        self.next += size;
+        Ok(size)
+    }
+}
+
+/// Quartet state of a decoded stream which arrives in chunks of any size, shared by the readers.
+pub(crate) struct StreamDecoder {
+    engine: &'static Base64Engine,
+    position: usize,
+    quartet: [u8; 4],
+    quartet_len: usize,
+    padded: bool,
+}
+
+impl StreamDecoder {
+    pub(crate) fn new(engine: &'static Base64Engine) -> StreamDecoder {
+        StreamDecoder { engine, position: 0, quartet: [0; 4], quartet_len: 0, padded: false }
+    }
+
+    /// Appends the bytes of the quartets completed by `chunk` to `decoded`.
+    pub(crate) fn push(&mut self, chunk: &[u8], decoded: &mut Vec<u8>) -> Result<(), Base64Error> {
+        for b in chunk {
+            if self.engine.decode_table()[*b as usize] == INVALID_CHAR {
+                return Err(invalid_character(*b, self.position));
+            }
+            if self.padded {
+                // nothing may follow a padded group
+                return Err(data_after_padding(self.position - 1));
+            }
+            self.quartet[self.quartet_len] = *b;
+            self.quartet_len += 1;
+            self.position += 1;
+            if self.quartet_len == 4 {
+                self.decode_quartet(decoded)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Handles the end of the stream, where only an unpadded engine accepts a short group.
+    pub(crate) fn finish(&mut self, decoded: &mut Vec<u8>) -> Result<(), Base64Error> {
+        match self.quartet_len {
+            0 => Ok(()),
+            2 | 3 if self.engine.pad_char().is_none() => self.decode_quartet(decoded),
+            count => Err(truncated(self.position - count, count))
+        }
+    }
+
+    fn decode_quartet(&mut self, decoded: &mut Vec<u8>) -> Result<(), Base64Error> {
+        let table = self.engine.decode_table();
+        let mut converted = [PADDING_CODE; 4];
+        for (i, b) in self.quartet[..self.quartet_len].iter().enumerate() {
+            converted[i] = table[*b as usize];
+        }
+        let start = self.position - self.quartet_len;
+        let (bytes, size) = decode_checked_quartet(converted, true)
+            .map_err(|i| invalid_character(self.quartet[i], start + i))?;
+        self.padded = size < 3;
+        decoded.extend_from_slice(&bytes[..size]);
+        self.quartet_len = 0;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{self, ErrorKind};
+
+    use super::*;
+
+    /// Hands out at most `step` bytes per read, like a slow socket.
+    struct Trickle<'a> {
+        data: &'a [u8],
+        step: usize,
+    }
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+            let size = self.step.min(buf.len()).min(self.data.len());
+            buf[..size].copy_from_slice(&self.data[..size]);
+            self.data = &self.data[size..];
+            Ok(size)
+        }
+    }
+
+    fn copy(input: &[u8], step: usize) -> Result<Vec<u8>, Error> {
+        let mut decoded = Vec::new();
+        let mut reader = Base64Reader::with_flavour(Trickle { data: input, step }, &Flavour::Base64Standard);
+        io::copy(&mut reader, &mut decoded)?;
+        Ok(decoded)
+    }
+
+    #[test]
+    fn when_copy_should_decode_whole_stream() {
+        let bytes: Vec<u8> = (0..10_000u32).map(|i| (i * 31 % 256) as u8).collect();
+        let encoded = crate::base64_encode_bytes(&bytes, &Flavour::Base64Standard);
+        for step in [1, 3, 5, 4096, 100_000] {
+            assert_eq!(bytes, copy(&encoded, step).unwrap());
+        }
+        assert!(copy(b"", 1).unwrap().is_empty());
+    }
+
+    #[test]
+    fn when_copy_malformed_should_fail_with_invalid_data() {
+        let error = copy(b"TWFuT!E=", 3).unwrap_err();
+        assert_eq!(ErrorKind::InvalidData, error.kind());
+        assert_eq!("Invalid character 0x21 at position 5", error.to_string());
+        assert_eq!("Truncated input: final group at position 4 has 2 characters", copy(b"TWFuTW", 2).unwrap_err().to_string());
+        assert_eq!("Padded group ending at position 3 is followed by more data", copy(b"TQ==TWFu", 1).unwrap_err().to_string());
+    }
+}
\ No newline at end of file
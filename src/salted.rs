@@ -0,0 +1,51 @@
+use crate::errors::{Base64Error, DecodeError, Flavour};
+use crate::{base64_decode_bytes, base64_encode_bytes};
+
+/// Encodes `bytes` with the default flavour behind `salt_len` random bytes, so equal inputs give
+/// different text. Returns the salt with the encoding. This is obfuscation, not encryption.
+///
+/// Panics if the operating system's random source fails.
+pub fn base64_encode_salted(bytes: &[u8], salt_len: usize) -> (String, Vec<u8>) {
+    let mut salted = vec![0; salt_len];
+    getrandom::getrandom(&mut salted).expect("the random source is available");
+    let salt = salted.clone();
+    salted.extend_from_slice(bytes);
+    let encoded = base64_encode_bytes(&salted, &Flavour::default());
+    (String::from_utf8(encoded).expect("base64 alphabets are ASCII"), salt)
+}
+
+/// Decodes text produced by [`base64_encode_salted`] and strips the `salt` it returned, failing
+/// if the decoded bytes do not start with it.
+pub fn base64_decode_salted(input: &[u8], salt: &[u8]) -> Result<Vec<u8>, Base64Error> {
+    let decoded = base64_decode_bytes(input, &Flavour::default())?;
+    match decoded.strip_prefix(salt) {
+        Some(bytes) => Ok(bytes.to_vec()),
+        None => Err(Base64Error::malformed(DecodeError::InvalidFormat,
+                                           &format!("Decoded bytes do not start with the {} byte salt", salt.len())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn when_salted_should_round_trip_with_the_returned_salt() {
+        let (encoded, salt) = base64_encode_salted(b"Man", 9);
+        assert_eq!(9, salt.len());
+        assert_eq!(16, encoded.len());
+        assert_eq!(b"Man".to_vec(), base64_decode_salted(encoded.as_bytes(), &salt).unwrap());
+        let (unsalted, salt) = base64_encode_salted(b"Man", 0);
+        assert_eq!("TWFu", unsalted);
+        assert_eq!(b"Man".to_vec(), base64_decode_salted(unsalted.as_bytes(), &salt).unwrap());
+    }
+
+    #[test]
+    fn when_salt_does_not_match_should_fail() {
+        let (encoded, mut salt) = base64_encode_salted(b"Man", 6);
+        salt[0] ^= 1;
+        let error = base64_decode_salted(encoded.as_bytes(), &salt).unwrap_err();
+        assert_eq!("Decoded bytes do not start with the 6 byte salt", error.msg);
+        assert_eq!(Some(DecodeError::InvalidFormat), error.decode_error);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,66 @@
+use crate::constants::PADDING_CHAR;
+use crate::errors::{Base64Error, DecodeError, Flavour};
+use crate::base64_decode_bytes;
+
+/// Splits encoded base64 into shards of at most `shard_size` characters for storage. The size is
+/// rounded down to a multiple of 4 (at least 4) so every shard holds whole quartets and decodes on
+/// its own; only the last shard may be shorter.
+pub fn shard_encoded(encoded: &[u8], shard_size: usize) -> Vec<Vec<u8>> {
+    let size = (shard_size / 4).max(1) * 4;
+    encoded.chunks(size).map(|shard| shard.to_vec()).collect()
+}
+
+/// Reassembles shards made by [`shard_encoded`] and decodes them with the default flavour. Every
+/// shard but the last must hold whole quartets without padding.
+pub fn decode_shards(shards: &[Vec<u8>]) -> Result<Vec<u8>, Base64Error> {
+    let last = shards.len().saturating_sub(1);
+    for (i, shard) in shards[..last].iter().enumerate() {
+        if !shard.len().is_multiple_of(4) {
+            return Err(Base64Error::malformed(DecodeError::InvalidLength,
+                                              &format!("Shard {} has {} characters, not whole quartets", i, shard.len())));
+        }
+        if shard.contains(&PADDING_CHAR) {
+            return Err(Base64Error::malformed(DecodeError::InvalidPadding,
+                                              &format!("Shard {} is padded but is not the last one", i)));
+        }
+    }
+    base64_decode_bytes(&shards.concat(), &Flavour::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base64_encode_bytes;
+
+    #[test]
+    fn when_shard_encoded_should_split_on_quartets() {
+        let encoded = base64_encode_bytes("Assuming Man".as_bytes(), &Flavour::Base64Standard);
+        assert_eq!(16, encoded.len());
+        let shards = shard_encoded(&encoded, 4);
+        assert_eq!(4, shards.len());
+        let decoded: Vec<Vec<u8>> = shards.iter()
+            .map(|shard| base64_decode_bytes(shard, &Flavour::Base64Standard).unwrap())
+            .collect();
+        assert_eq!(vec![b"Ass".to_vec(), b"umi".to_vec(), b"ng ".to_vec(), b"Man".to_vec()], decoded);
+        let shards = shard_encoded(&encoded, 7);
+        assert_eq!(vec![4; 4], shards.iter().map(Vec::len).collect::<Vec<_>>());
+        let shards = shard_encoded(&encoded, 12);
+        assert_eq!(vec![12, 4], shards.iter().map(Vec::len).collect::<Vec<_>>());
+        assert!(shard_encoded(b"", 8).is_empty());
+    }
+
+    #[test]
+    fn when_decode_shards_should_reassemble_original() {
+        let bytes = "Assuming Man, again".as_bytes();
+        let encoded = base64_encode_bytes(bytes, &Flavour::default());
+        assert_eq!(bytes, decode_shards(&shard_encoded(&encoded, 4)).unwrap().as_slice());
+        assert_eq!(bytes, decode_shards(&shard_encoded(&encoded, 9)).unwrap().as_slice());
+        assert!(decode_shards(&[]).unwrap().is_empty());
+        let error = decode_shards(&[b"TQ==".to_vec(), b"TWFu".to_vec()]).unwrap_err();
+        assert_eq!("Shard 0 is padded but is not the last one", error.msg);
+        assert_eq!(Some(DecodeError::InvalidPadding), error.decode_error);
+        let error = decode_shards(&[b"TWF".to_vec(), b"uTWFu".to_vec()]).unwrap_err();
+        assert_eq!("Shard 0 has 3 characters, not whole quartets", error.msg);
+        assert_eq!(Some(DecodeError::InvalidLength), error.decode_error);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,44 @@
+use smallvec::SmallVec;
+
+use crate::errors::{Base64Error, Flavour};
+use crate::{decode_calc_byte_size, decode_into, strip_bom};
+
+/// Decodes base64 of the default flavour into a `SmallVec`, which keeps up to 32 decoded bytes,
+/// e.g. a token or a hash, on the stack and only allocates for longer outputs. A leading UTF-8 BOM
+/// is skipped, as by [`crate::base64_decode_bytes`].
+pub fn base64_decode_smallvec(input: &[u8]) -> Result<SmallVec<[u8; 32]>, Base64Error> {
+    let engine = Flavour::default().engine();
+    let input = strip_bom(input);
+    let mut res = SmallVec::from_elem(0, decode_calc_byte_size(input, engine));
+    let size = decode_into(input, engine, &mut res)?;
+    res.truncate(size);
+    Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base64_encode_bytes;
+
+    #[test]
+    fn when_decode_smallvec_output_is_short_should_stay_inline() {
+        let token: Vec<u8> = (0..16).collect();
+        let decoded = base64_decode_smallvec(&base64_encode_bytes(&token, &Flavour::default())).unwrap();
+        assert_eq!(token.as_slice(), decoded.as_slice());
+        assert!(!decoded.spilled());
+    }
+
+    #[test]
+    fn when_decode_smallvec_output_is_long_should_spill_to_the_heap() {
+        let token: Vec<u8> = (0..64).collect();
+        let decoded = base64_decode_smallvec(&base64_encode_bytes(&token, &Flavour::default())).unwrap();
+        assert_eq!(token.as_slice(), decoded.as_slice());
+        assert!(decoded.spilled());
+        assert_eq!("Invalid character 0x21 at position 3", base64_decode_smallvec(b"TWF!").unwrap_err().msg);
+    }
+
+    #[test]
+    fn when_decode_smallvec_with_bom_should_skip_it() {
+        assert_eq!("Man".as_bytes(), base64_decode_smallvec(b"\xef\xbb\xbfTWFu").unwrap().as_slice());
+    }
+}
\ No newline at end of file
@@ -0,0 +1,54 @@
+//! The functions which do not take a flavour, with the URL safe alphabet instead of the standard
+//! one, for code which only ever uses base64url:
+//!
+//! ```
+//! use simple_base64::url_safe::{try_decode, try_encode};
+//!
+//! assert_eq!("-_8=".as_bytes(), try_encode(&[0xfb, 0xff]).unwrap().as_slice());
+//! assert_eq!(vec![0xfb, 0xff], try_decode(b"-_8=").unwrap());
+//! ```
+//!
+//! The functions at the crate root keep the standard alphabet, whoever enables the feature.
+
+use std::str::Utf8Error;
+
+use crate::errors::Base64Error;
+use crate::Flavour::Base64Url;
+pub use crate::fixed::{decode_fixed_url as decode_fixed, encode_fixed_url as encode_fixed};
+
+/// [`crate::try_encode`] with the URL safe alphabet.
+pub fn try_encode(bytes: &[u8]) -> Result<Vec<u8>, Base64Error> {
+    crate::try_encode_with(bytes, &Base64Url)
+}
+
+/// [`crate::try_decode`] with the URL safe alphabet.
+pub fn try_decode(bytes: &[u8]) -> Result<Vec<u8>, Base64Error> {
+    crate::base64_decode_bytes(bytes, &Base64Url)
+}
+
+/// [`crate::base64_encode`] with the URL safe alphabet.
+pub fn base64_encode(str: String) -> Result<String, Utf8Error> {
+    crate::base64_encode(str, &Base64Url)
+}
+
+/// [`crate::base64_decode`] with the URL safe alphabet.
+pub fn base64_decode(str: String) -> Result<String, Base64Error> {
+    crate::base64_decode(str, &Base64Url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn when_url_safe_functions_should_use_url_alphabet() {
+        assert_eq!("SXMgaXQgfn5-Pw==", base64_encode("Is it ~~~?".to_string()).unwrap());
+        assert_eq!("Is it ~~~?", base64_decode("SXMgaXQgfn5-Pw==".to_string()).unwrap());
+        assert_eq!("SXMgaXQgfn5-Pw==".as_bytes(), try_encode("Is it ~~~?".as_bytes()).unwrap().as_slice());
+        assert_eq!(vec![0xfb, 0xff], try_decode("-_8=".as_bytes()).unwrap());
+        assert_eq!(*b"-_8=", encode_fixed(&[0xfb, 0xff]));
+        assert_eq!([0xfb, 0xff], decode_fixed::<2>(b"-_8=").unwrap());
+        // the crate root is left alone
+        assert_eq!("SXMgaXQgfn5+Pw==".as_bytes(), crate::try_encode("Is it ~~~?".as_bytes()).unwrap().as_slice());
+    }
+}
\ No newline at end of file
@@ -0,0 +1,219 @@
+use std::io::{Error, ErrorKind, Write};
+
+use crate::engine::Base64Engine;
+use crate::errors::Flavour;
+use crate::{encode_calc_byte_size, encode_into};
+
+const CHUNK: usize = 3 * 1024;
+
+/// Encodes everything written to it into another writer, the counterpart of
+/// [`crate::Base64Reader`]. Whole groups are written as they complete, the final partial group,
+/// with its padding, by [`Base64Writer::finish`].
+///
+/// Like `BufWriter`, a writer dropped without `finish` still writes its final group, but errors
+/// are then ignored: call `finish` to handle them.
+///
+/// ```
+/// use std::io::Write;
+/// use simple_base64::Base64Writer;
+///
+/// let mut writer = Base64Writer::new(Vec::new());
+/// writer.write_all(b"Ma").unwrap();
+/// assert_eq!("TWE=".as_bytes(), writer.finish().unwrap().as_slice());
+/// ```
+pub struct Base64Writer<W: Write> {
+    inner: Option<W>,
+    encoder: StreamEncoder,
+    encoded: Vec<u8>,
+}
+
+impl<W: Write> Base64Writer<W> {
+    /// Encodes with the default flavour.
+    pub fn new(inner: W) -> Base64Writer<W> {
+        Base64Writer::with_flavour(inner, &Flavour::default())
+    }
+
+    pub fn with_flavour(inner: W, flavour: &Flavour) -> Base64Writer<W> {
+        Base64Writer { inner: Some(inner), encoder: StreamEncoder::new(flavour.engine()), encoded: Vec::new() }
+    }
+
+    /// Writes the final group and flushes, returning the inner writer.
+    pub fn finish(mut self) -> Result<W, Error> {
+        self.write_final_group()?;
+        let mut inner = self.inner.take().expect("the inner writer is only taken by finish");
+        inner.flush()?;
+        Ok(inner)
+    }
+
+    fn write_final_group(&mut self) -> Result<(), Error> {
+        self.encoder.finish(&mut self.encoded);
+        self.write_encoded()
+    }
+
+    /// Writes the encoded characters to the inner writer. Those it did not take stay buffered
+    /// when it fails, to be written first by the next call.
+    fn write_encoded(&mut self) -> Result<(), Error> {
+        let Some(inner) = self.inner.as_mut() else {
+            return Ok(());
+        };
+        while !self.encoded.is_empty() {
+            match inner.write(&self.encoded) {
+                Ok(0) => return Err(Error::from(ErrorKind::WriteZero)),
+                Ok(written) => {
+                    self.encoded.drain(..written);
+                }
+                Err(error) if error.kind() == ErrorKind::Interrupted => {}
+                Err(error) => return Err(error)
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<W: Write> Write for Base64Writer<W> {
+    /// Encodes at most one chunk of `buf`, so that a large write does not buffer all of its
+    /// encoding. An error is only returned before anything of `buf` is consumed: once the chunk is
+    /// encoded, a failing inner writer is reported by the next call.
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Error> {
+        self.write_encoded()?;
+        let chunk = &buf[..buf.len().min(CHUNK)];
+        self.encoder.push(chunk, &mut self.encoded);
+        let _ = self.write_encoded();
+        Ok(chunk.len())
+    }
+
+    /// Writes the encoded characters left by a failed write and flushes the inner writer. The final
+    /// partial group stays buffered, since more bytes may still complete it.
+    fn flush(&mut self) -> Result<(), Error> {
+        self.write_encoded()?;
+        match self.inner.as_mut() {
+            Some(inner) => inner.flush(),
+            None => Ok(())
+        }
+    }
+}
+
+impl<W: Write> Drop for Base64Writer<W> {
+    fn drop(&mut self) {
+        if self.inner.is_some() {
+            // errors cannot be reported from drop, finish is the way to see them
+            let _ = self.write_final_group();
+            let _ = self.flush();
+        }
+    }
+}
+
+/// Trio state of an encoded stream which arrives in chunks of any size, shared by the writers.
+pub(crate) struct StreamEncoder {
+    engine: &'static Base64Engine,
+    trio: [u8; 3],
+    trio_len: usize,
+}
+
+impl StreamEncoder {
+    pub(crate) fn new(engine: &'static Base64Engine) -> StreamEncoder {
+        StreamEncoder { engine, trio: [0; 3], trio_len: 0 }
+    }
+
+    /// Appends the characters of the trios completed by `chunk` to `encoded`.
+    pub(crate) fn push(&mut self, chunk: &[u8], encoded: &mut Vec<u8>) {
+        // complete the buffered trio first, then encode the whole trios and keep the rest
+        let mut bytes = chunk;
+        if self.trio_len > 0 {
+            let taken = bytes.len().min(3 - self.trio_len);
+            self.trio[self.trio_len..self.trio_len + taken].copy_from_slice(&bytes[..taken]);
+            self.trio_len += taken;
+            bytes = &bytes[taken..];
+            if self.trio_len < 3 {
+                return;
+            }
+            let trio = self.trio;
+            self.encode(&trio, encoded);
+            self.trio_len = 0;
+        }
+        let whole = bytes.len() / 3 * 3;
+        self.encode(&bytes[..whole], encoded);
+        self.trio_len = bytes.len() - whole;
+        self.trio[..self.trio_len].copy_from_slice(&bytes[whole..]);
+    }
+
+    /// Appends the final partial group with its padding, at the end of the stream.
+    pub(crate) fn finish(&mut self, encoded: &mut Vec<u8>) {
+        let trio = self.trio;
+        self.encode(&trio[..self.trio_len], encoded);
+        self.trio_len = 0;
+    }
+
+    fn encode(&self, bytes: &[u8], encoded: &mut Vec<u8>) {
+        let start = encoded.len();
+        encoded.resize(start + encode_calc_byte_size(bytes, self.engine.pad_char().is_some()), 0);
+        encode_into(bytes, self.engine, &mut encoded[start..]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base64_encode_bytes;
+
+    #[test]
+    fn when_dropped_after_partial_writes_should_write_final_group() {
+        let bytes: Vec<u8> = (0..10_000u32).map(|i| (i * 43 % 256) as u8).collect();
+        let mut out = Vec::new();
+        {
+            let mut writer = Base64Writer::with_flavour(&mut out, &Flavour::Base64Standard);
+            for chunk in bytes.chunks(7) {
+                writer.write_all(chunk).unwrap();
+            }
+        }
+        assert_eq!(base64_encode_bytes(&bytes, &Flavour::Base64Standard), out);
+    }
+
+    #[test]
+    fn when_finish_should_return_complete_output() {
+        let mut writer = Base64Writer::with_flavour(Vec::new(), &Flavour::Base64Standard);
+        writer.write_all(b"M").unwrap();
+        writer.write_all(b"a").unwrap();
+        writer.flush().unwrap();
+        assert_eq!("TWE=".as_bytes(), writer.finish().unwrap().as_slice());
+        let writer = Base64Writer::with_flavour(Vec::new(), &Flavour::Base64Standard);
+        assert!(writer.finish().unwrap().is_empty());
+    }
+
+    /// Fails every other write, before taking anything.
+    struct Flaky {
+        out: Vec<u8>,
+        fail: bool,
+    }
+
+    impl Write for Flaky {
+        fn write(&mut self, buf: &[u8]) -> Result<usize, Error> {
+            self.fail = !self.fail;
+            if self.fail {
+                return Err(Error::other("flaky"));
+            }
+            self.out.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn when_inner_writer_fails_should_neither_lose_nor_repeat_output() {
+        let bytes: Vec<u8> = (0..10_000u32).map(|i| (i * 43 % 256) as u8).collect();
+        let mut writer = Base64Writer::with_flavour(Flaky { out: Vec::new(), fail: false }, &Flavour::Base64Standard);
+        let mut consumed = 0;
+        while consumed < bytes.len() {
+            if let Ok(written) = writer.write(&bytes[consumed..]) {
+                assert!(written > 0);
+                consumed += written;
+            }
+        }
+        while writer.flush().is_err() {}
+        let encoded = base64_encode_bytes(&bytes, &Flavour::Base64Standard);
+        assert_eq!(encoded[..encoded.len() - 4], writer.inner.as_ref().unwrap().out[..]);
+    }
+}
\ No newline at end of file
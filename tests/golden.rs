@@ -0,0 +1,44 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use simple_base64::{base64_decode_bytes, base64_encode_bytes, Flavour};
+
+const FIXTURES: &str = "tests/fixtures";
+
+/// Every `<name>.bin` fixture has golden encodings `<name>.b64` (standard) and `<name>.url.b64`
+/// (URL safe), produced by an independent implementation.
+fn fixtures() -> Vec<PathBuf> {
+    let mut bins: Vec<PathBuf> = fs::read_dir(FIXTURES).unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "bin"))
+        .collect();
+    bins.sort();
+    assert!(!bins.is_empty());
+    bins
+}
+
+fn golden(bin: &Path, suffix: &str) -> Vec<u8> {
+    let stem = bin.file_stem().unwrap().to_str().unwrap();
+    fs::read(Path::new(FIXTURES).join(format!("{}{}", stem, suffix))).unwrap()
+}
+
+fn check(flavour: &Flavour, suffix: &str) {
+    for bin in fixtures() {
+        let data = fs::read(&bin).unwrap();
+        let expected = golden(&bin, suffix);
+        let encoded = base64_encode_bytes(&data, flavour);
+        assert_eq!(String::from_utf8_lossy(&expected), String::from_utf8_lossy(&encoded),
+                   "encoding of {} changed", bin.display());
+        assert_eq!(data, base64_decode_bytes(&expected, flavour).unwrap(), "decoding of {} changed", bin.display());
+    }
+}
+
+#[test]
+fn when_encode_fixtures_should_match_standard_golden_files() {
+    check(&Flavour::Base64Standard, ".b64");
+}
+
+#[test]
+fn when_encode_fixtures_should_match_url_safe_golden_files() {
+    check(&Flavour::Base64Url, ".url.b64");
+}